@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Compares downloading a range of blocks from a regtest node one at a time with
+//! [`Client::fetch_blocks`] using an increasing number of workers.
+//!
+//! Run with `cargo bench --bench fetch_blocks`.
+
+use std::time::{Duration, Instant};
+
+use bdk_bitcoind_client::Client;
+
+#[path = "../tests/testenv.rs"]
+mod testenv;
+
+use testenv::TestEnv;
+
+/// Height of the last downloaded block.
+const TIP: u32 = 500;
+
+fn main() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let _hashes = env.mine_blocks(TIP as usize, None)?;
+
+    report("sequential", sequential(&env.client))?;
+    for workers in [1, 2, 4, 8] {
+        report(
+            &format!("{workers} workers"),
+            parallel(&env.client, workers),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Downloads the blocks with `getblockhash` and `getblock`, one height at a time.
+fn sequential(client: &Client) -> anyhow::Result<Duration> {
+    let start = Instant::now();
+    for height in 0..=TIP {
+        let hash = client.get_block_hash(height)?;
+        client.get_block(&hash)?;
+    }
+    Ok(start.elapsed())
+}
+
+/// Downloads the blocks with `Client::fetch_blocks` using `workers` connections.
+fn parallel(client: &Client, workers: usize) -> anyhow::Result<Duration> {
+    let start = Instant::now();
+    for result in client.fetch_blocks(0..=TIP, workers)? {
+        result?;
+    }
+    Ok(start.elapsed())
+}
+
+fn report(name: &str, elapsed: anyhow::Result<Duration>) -> anyhow::Result<()> {
+    let elapsed = elapsed?;
+    let rate = f64::from(TIP + 1) / elapsed.as_secs_f64();
+    println!("{name:>12}: {elapsed:>10.2?}, {rate:>8.1} blocks/s");
+    Ok(())
+}
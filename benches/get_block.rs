@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Compares the ways of fetching a block from a regtest node:
+//!
+//! * `hex`: `getblock` with verbosity 0, deserializing the block from the hex `String`.
+//! * `rpc bytes`: [`Client::get_block_bytes`] over JSON-RPC, decoding the hex from the response.
+//! * `rest bytes`: [`Client::get_block_bytes`] over the binary REST interface.
+//!
+//! Run with `cargo bench --bench get_block`.
+
+use std::time::{Duration, Instant};
+
+use bdk_bitcoind_client::{Auth, Client, ClientBuilder};
+use corepc_types::bitcoin::{
+    Amount, Block, BlockHash,
+    consensus::encode::{deserialize, deserialize_hex},
+};
+use jsonrpc::serde_json::json;
+
+#[path = "../tests/testenv.rs"]
+mod testenv;
+
+use testenv::TestEnv;
+
+/// Number of transactions in the fetched block.
+const TXS: usize = 1_000;
+/// Number of times the block is fetched with each method.
+const ITERATIONS: u32 = 50;
+
+fn main() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let address = env.bitcoind.client.new_address()?;
+    let _hashes = env.mine_blocks(101, Some(address.clone()))?;
+    for _ in 0..TXS {
+        env.bitcoind
+            .client
+            .send_to_address(&address, Amount::from_sat(10_000))?;
+    }
+    let hash = env.mine_blocks(1, None)?[0];
+    let size = env.client.get_block_bytes(&hash)?.len();
+
+    let rest_client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .rest(true)
+        .build()?;
+
+    println!("block of {} transactions, {size} bytes", TXS + 1);
+    report("hex", size, bench(|| get_block_hex(&env.client, &hash)))?;
+    report("rpc bytes", size, bench(|| get_block(&env.client, &hash)))?;
+    report("rest bytes", size, bench(|| get_block(&rest_client, &hash)))?;
+
+    Ok(())
+}
+
+/// Fetches the block the way `Client::get_block` used to, deserializing it from a hex `String`.
+fn get_block_hex(client: &Client, hash: &BlockHash) -> anyhow::Result<Block> {
+    let hex: String = client.call("getblock", &[json!(hash), json!(0)])?;
+    Ok(deserialize_hex(&hex)?)
+}
+
+/// Fetches the block with `Client::get_block_bytes`.
+fn get_block(client: &Client, hash: &BlockHash) -> anyhow::Result<Block> {
+    Ok(deserialize(&client.get_block_bytes(hash)?)?)
+}
+
+/// Returns the mean time taken by `f`.
+fn bench(mut f: impl FnMut() -> anyhow::Result<Block>) -> anyhow::Result<Duration> {
+    // Warm up the connection and the node's block cache.
+    f()?;
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f()?;
+    }
+    Ok(start.elapsed() / ITERATIONS)
+}
+
+fn report(name: &str, size: usize, mean: anyhow::Result<Duration>) -> anyhow::Result<()> {
+    let mean = mean?;
+    let throughput = size as f64 / mean.as_secs_f64() / 1_000_000.0;
+    println!("{name:>12}: {mean:>10.2?} per block, {throughput:>8.1} MB/s");
+    Ok(())
+}
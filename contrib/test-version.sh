@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+
+# This script will download a Bitcoin Core release and
+# run the tests against it, e.g. `contrib/test-version.sh 28.2`.
+
+set -euo pipefail
+
+VERSION=${1:?usage: $0 <bitcoin core version, e.g. 28.2>}
+DIR="target/bitcoin-core/$VERSION"
+
+case "$(uname -s)-$(uname -m)" in
+    Linux-x86_64) PLATFORM=x86_64-linux-gnu ;;
+    Linux-aarch64) PLATFORM=aarch64-linux-gnu ;;
+    Darwin-x86_64) PLATFORM=x86_64-apple-darwin ;;
+    Darwin-arm64) PLATFORM=arm64-apple-darwin ;;
+    *) echo "Unsupported platform: $(uname -s)-$(uname -m)"; exit 1 ;;
+esac
+
+if [ ! -x "$DIR/bitcoin-$VERSION/bin/bitcoind" ]; then
+    URL="https://bitcoincore.org/bin/bitcoin-core-$VERSION"
+    TARBALL="bitcoin-$VERSION-$PLATFORM.tar.gz"
+    mkdir -p "$DIR"
+    curl -fsSL -o "$DIR/$TARBALL" "$URL/$TARBALL"
+    curl -fsSL -o "$DIR/SHA256SUMS" "$URL/SHA256SUMS"
+    (cd "$DIR" && grep " $TARBALL\$" SHA256SUMS | shasum -a 256 -c -)
+    tar -xzf "$DIR/$TARBALL" -C "$DIR"
+fi
+
+BITCOIND_EXE="$PWD/$DIR/bitcoin-$VERSION/bin/bitcoind" cargo test --all-features
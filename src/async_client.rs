@@ -0,0 +1,929 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use corepc_types::{
+    bitcoin::{
+        Block, BlockHash, FeeRate, Network, Transaction, Txid,
+        block::Header,
+        consensus::encode::{deserialize, deserialize_hex, serialize_hex},
+    },
+    model::{
+        CreateWallet, GetBlockCount, GetBlockFilter, GetBlockHeaderVerbose, GetBlockVerboseOne,
+        GetBlockchainInfo, GetChainTips, GetChainTxStats, GetRawMempool, GetRawTransactionVerbose,
+        ListWallets, LoadWallet, MempoolAcceptance, SubmitPackage, UnloadWallet,
+    },
+    v28,
+    v30::{self, GetZmqNotifications},
+};
+use jsonrpc::{
+    Request, Response, serde,
+    serde_json::{self, json},
+};
+
+use crate::broadcast::{BroadcastOptions, TxRejection};
+use crate::builder::ClientBuilder;
+use crate::client::{Auth, HexBytes, timeout_millis, verify_network};
+use crate::error::Error;
+use crate::fees::{self, EstimateMode};
+use crate::prune::{self, PeerId};
+use crate::rest::{self, RestClient};
+use crate::retry::RetryPolicy;
+use crate::transport::HttpTransport;
+use crate::version::{CoreVersion, NetworkInfoVersion};
+
+/// Asynchronous Bitcoin Core JSON-RPC Client.
+///
+/// Mirrors the method surface of [`Client`](crate::Client), but sends requests over an
+/// async [`bitreq`] transport running on `tokio`. It is configured with a [`ClientBuilder`], see
+/// [`ClientBuilder::build_async`].
+///
+/// The following parts of [`Client`](crate::Client) are not available, since they are built on
+/// top of the blocking transport:
+///
+/// * [`Client::batch`](crate::Client::batch): send several calls concurrently instead, `bitcoind`
+///   serves up to `-rpcthreads` of them in parallel.
+/// * [`Client::fetch_blocks`](crate::Client::fetch_blocks), which downloads blocks on worker
+///   threads: join several [`AsyncClient::get_block`] futures instead.
+/// * [`Client::with_transport`](crate::Client::with_transport): the transport is always the HTTP
+///   transport of the [`ClientBuilder`].
+#[derive(Debug)]
+pub struct AsyncClient {
+    /// The HTTP transport.
+    transport: HttpTransport,
+    /// Counter used to generate request IDs.
+    nonce: AtomicUsize,
+    /// The server version, detected on first use.
+    version: OnceLock<CoreVersion>,
+    /// How calls failing with transient errors are retried, if at all.
+    retry_policy: Option<RetryPolicy>,
+    /// The configuration the client was built with.
+    builder: ClientBuilder,
+    /// The client for the REST interface.
+    rest: RestClient,
+    /// Whether the node turned out to serve blocks over JSON-RPC but not over REST.
+    rest_unavailable: AtomicBool,
+}
+
+impl AsyncClient {
+    /// Creates an async client connection to a bitcoind JSON-RPC server with authentication.
+    ///
+    /// For a custom configuration, use [`ClientBuilder::build_async`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the RPC server
+    /// * `auth` - authentication method (`UserPass` or `CookieFile`)
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidUrl` if the URL is invalid.
+    /// * Returns errors related to reading the cookie file.
+    pub fn with_auth(url: &str, auth: Auth) -> Result<Self, Error> {
+        Self::from_builder(ClientBuilder::new(url).auth(auth))
+    }
+
+    /// Creates an async client connection to the node running on `network`, with the URL and
+    /// authentication read from the `bitcoin.conf` in `datadir`, or the default data directory.
+    ///
+    /// See [`RpcConfig::from_datadir`](crate::RpcConfig::from_datadir) for how the configuration
+    /// is located and read.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if the configuration can't be located or is invalid.
+    /// * Returns errors related to reading `bitcoin.conf` or the cookie file.
+    pub fn from_datadir(network: Network, datadir: Option<&Path>) -> Result<Self, Error> {
+        Self::from_builder(ClientBuilder::from_datadir(network, datadir)?)
+    }
+
+    /// Creates a client with the HTTP transport and retry policy configured in `builder`.
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Result<Self, Error> {
+        Ok(Self {
+            transport: builder.transport()?,
+            nonce: AtomicUsize::new(1),
+            version: OnceLock::new(),
+            retry_policy: builder.retry_policy.clone(),
+            rest: builder.clone().build_rest()?,
+            rest_unavailable: AtomicBool::new(false),
+            builder,
+        })
+    }
+
+    /// Returns a client for the wallet `name`, sharing the URL, authentication and configuration of
+    /// this client.
+    ///
+    /// See [`Client::wallet`](crate::Client::wallet).
+    ///
+    /// # Errors
+    ///
+    /// * Returns errors related to reading the cookie file.
+    pub fn wallet(&self, name: &str) -> Result<AsyncClient, Error> {
+        let mut client = Self::from_builder(self.builder.clone().wallet(name))?;
+        client.retry_policy = self.retry_policy.clone();
+        if let Some(version) = self.version.get() {
+            let _ = client.version.set(*version);
+        }
+
+        Ok(client)
+    }
+
+    /// Returns the name of the wallet this client sends requests to, if any.
+    pub fn wallet_name(&self) -> Option<&str> {
+        self.builder.wallet.as_deref()
+    }
+
+    /// Fetches blocks over the REST interface, which transfers them in binary rather than hex.
+    ///
+    /// See [`ClientBuilder::rest`].
+    pub fn with_rest(mut self, enabled: bool) -> Self {
+        self.builder = self.builder.rest(enabled);
+        self
+    }
+
+    /// Downloads pruned blocks again from a peer with `getblockfrompeer`, waiting up to `timeout`
+    /// for them to arrive.
+    ///
+    /// See [`ClientBuilder::fetch_pruned_blocks`].
+    pub fn with_pruned_block_fetching(mut self, timeout: Duration) -> Self {
+        self.builder = self.builder.fetch_pruned_blocks(timeout);
+        self
+    }
+
+    /// Retries calls failing with transient errors according to `policy`.
+    ///
+    /// By default, calls are not retried. Broadcasts are never retried, see [`RetryPolicy`].
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Calls the underlying RPC `method` with the given `args`.
+    ///
+    /// This is the generic function used by all specific RPC methods. If a [`RetryPolicy`] is set,
+    /// calls failing with a retryable error are retried, sleeping in between.
+    pub async fn call<T>(&self, method: &str, args: &[serde_json::Value]) -> Result<T, Error>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        self.call_with_timeout(method, args, Duration::ZERO).await
+    }
+
+    /// Calls the RPC `method` with the given `args`, extending the request timeout by `timeout`.
+    ///
+    /// Used by long polls, which the node only answers after up to `timeout`.
+    async fn call_with_timeout<T>(
+        &self,
+        method: &str,
+        args: &[serde_json::Value],
+        timeout: Duration,
+    ) -> Result<T, Error>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        let raw = serde_json::value::to_raw_value(args)?;
+        let timeout = self.transport.timeout.saturating_add(timeout);
+        let mut attempt = 1;
+        loop {
+            let request = Request {
+                method,
+                params: Some(&*raw),
+                id: json!(self.nonce.fetch_add(1, Ordering::Relaxed)),
+                jsonrpc: Some("2.0"),
+            };
+            let result = self
+                .transport
+                .request_async::<Response>(&request, timeout)
+                .await
+                .map_err(jsonrpc::Error::from)
+                .and_then(|resp| resp.result())
+                .map_err(Error::from);
+            match (result, &self.retry_policy) {
+                (Err(e), Some(policy)) if policy.should_retry(method, attempt, &e) => {
+                    tokio::time::sleep(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    /// Returns the [`CoreVersion`] of the server.
+    ///
+    /// The version is queried with `getnetworkinfo` on the first call and cached afterwards.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::UnsupportedVersion` if the server is older than
+    ///   [`CoreVersion::MIN_SUPPORTED`].
+    pub async fn server_version(&self) -> Result<CoreVersion, Error> {
+        if let Some(version) = self.version.get() {
+            return Ok(*version);
+        }
+        let info: NetworkInfoVersion = self.call("getnetworkinfo", &[]).await?;
+        let version = CoreVersion::from_server_version(info.version)?;
+
+        Ok(*self.version.get_or_init(|| version))
+    }
+
+    /// Verifies that the node is on `network`.
+    ///
+    /// Both the chain reported by `getblockchaininfo` and the hash of the genesis block are
+    /// checked.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::NetworkMismatch` if the node is on another network.
+    pub async fn check_network(&self, network: Network) -> Result<(), Error> {
+        let info = self.get_blockchain_info().await?;
+        let genesis_hash = self.get_block_hash(0).await?;
+        verify_network(network, info.chain, genesis_hash)
+    }
+
+    /// Sends a `GET` request for `path` to the REST interface and returns the response body.
+    async fn get_rest(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.rest
+            .transport
+            .get_async(path)
+            .await
+            .map_err(Error::Rest)
+    }
+}
+
+/// `bitcoind` RPC methods implementation for `AsyncClient`.
+impl AsyncClient {
+    /// Retrieves the raw block data for a given block hash (verbosity 0).
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The deserialized `Block` struct.
+    ///
+    /// The block is fetched with [`AsyncClient::get_block_bytes`], over the REST interface if
+    /// enabled, and fails with `Error::BlockPruned` if the node pruned it.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::Decode` if the block is malformed. Earlier versions returned
+    ///   `Error::DecodeHex` instead, see the changelog.
+    pub async fn get_block(&self, block_hash: &BlockHash) -> Result<Block, Error> {
+        let bytes = self.get_block_bytes(block_hash).await?;
+        deserialize(&bytes).map_err(Error::Decode)
+    }
+
+    /// Retrieves the consensus-encoded bytes of the block with the given hash.
+    ///
+    /// If the client was built with [`ClientBuilder::rest`], the block is transferred
+    /// in binary from the REST interface, falling back to the JSON-RPC `getblock` if the REST
+    /// request fails with `404 Not Found` or `403 Forbidden`. If the block is then returned over
+    /// JSON-RPC, the node runs without `-rest=1` and the REST interface is no longer tried.
+    /// Otherwise, the hex returned by `getblock` is decoded straight from the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The serialized block, which can be deserialized lazily, e.g. with
+    /// [`consensus::deserialize`](corepc_types::bitcoin::consensus::deserialize).
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::Rest` if the REST request fails with another error.
+    /// * Returns `Error::BlockPruned` if the node pruned the block, and it was not downloaded again
+    ///   in time if the client was created
+    ///   [`with_pruned_block_fetching`](AsyncClient::with_pruned_block_fetching).
+    pub async fn get_block_bytes(&self, block_hash: &BlockHash) -> Result<Vec<u8>, Error> {
+        let rest_enabled = self.builder.rest && !self.rest_unavailable.load(Ordering::Relaxed);
+        let mut rest_failed = false;
+        if rest_enabled {
+            match self
+                .get_rest(&format!("/rest/block/{block_hash}.bin"))
+                .await
+            {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) if rest::should_fall_back(&e) => rest_failed = true,
+                Err(e) => return Err(e),
+            }
+        }
+        let result = self.get_block_bytes_rpc(block_hash).await;
+        if rest_failed && result.is_ok() {
+            self.rest_unavailable.store(true, Ordering::Relaxed);
+        }
+        match (&result, self.builder.pruned_block_timeout) {
+            (Err(Error::BlockPruned(_)), Some(timeout)) => {
+                self.fetch_pruned_block(block_hash, timeout).await
+            }
+            _ => result,
+        }
+    }
+
+    /// Retrieves the consensus-encoded bytes of a block with `getblock`.
+    async fn get_block_bytes_rpc(&self, block_hash: &BlockHash) -> Result<Vec<u8>, Error> {
+        self.call::<HexBytes>("getblock", &[json!(block_hash), json!(0)])
+            .await
+            .map(|bytes| bytes.0)
+            .map_err(|e| prune::classify_block_error(block_hash, e))
+    }
+
+    /// Requests a pruned block from the peers of the node, and waits up to `timeout` for it to
+    /// arrive.
+    async fn fetch_pruned_block(
+        &self,
+        block_hash: &BlockHash,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Error> {
+        let deadline = Instant::now() + timeout;
+        let peers = self.call::<Vec<PeerId>>("getpeerinfo", &[]).await?;
+        let mut requested = false;
+        for peer in &peers {
+            if self.get_block_from_peer(block_hash, peer.id).await.is_ok() {
+                requested = true;
+                break;
+            }
+        }
+        loop {
+            match self.get_block_bytes_rpc(block_hash).await {
+                Err(Error::BlockPruned(_)) if requested && Instant::now() < deadline => {
+                    tokio::time::sleep(prune::POLL_INTERVAL).await
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Retrieves the hash of the best chain's block.
+    ///
+    /// # Returns
+    ///
+    /// The `BlockHash` of the chain tip.
+    pub async fn get_best_block_hash(&self) -> Result<BlockHash, Error> {
+        let blockhash_hex: String = self.call("getbestblockhash", &[]).await?;
+        blockhash_hex.parse().map_err(Error::HexToArray)
+    }
+
+    /// Retrieves the number of blocks in the longest chain.
+    ///
+    /// # Returns
+    ///
+    /// The block count as a `u32`
+    pub async fn get_block_count(&self) -> Result<u32, Error> {
+        self.call::<GetBlockCount>("getblockcount", &[])
+            .await?
+            .0
+            .try_into()
+            .map_err(Error::TryFromInt)
+    }
+
+    /// Retrieves the [`BlockHash`] of the block at `height`.
+    ///
+    /// # Arguments
+    ///
+    /// * `height`: The block height
+    ///
+    /// # Returns
+    ///
+    /// The [`BlockHash`] of the block at `height`
+    pub async fn get_block_hash(&self, height: u32) -> Result<BlockHash, Error> {
+        let blockhash_hex: String = self.call("getblockhash", &[json!(height)]).await?;
+        blockhash_hex.parse().map_err(Error::HexToArray)
+    }
+
+    /// Retrieve the Compact Block Filter (BIP-0158) with type `basic` for the block given its `Blockhash`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block whose filter is requested
+    ///
+    /// # Returns
+    ///
+    /// The `GetBlockFilter` structure containing the filter data for the block
+    pub async fn get_block_filter(&self, block_hash: &BlockHash) -> Result<GetBlockFilter, Error> {
+        let block_filter: v30::GetBlockFilter =
+            self.call("getblockfilter", &[json!(block_hash)]).await?;
+        block_filter.into_model().map_err(Error::GetBlockFilter)
+    }
+
+    /// Retrieves the `Header` for a `Block` given its `BlockHash`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block whose header is requested.
+    ///
+    /// # Returns
+    ///
+    /// The deserialized `Header` struct
+    pub async fn get_block_header(&self, block_hash: &BlockHash) -> Result<Header, Error> {
+        let header_hex: String = self
+            .call("getblockheader", &[json!(block_hash), json!(false)])
+            .await?;
+        deserialize_hex(&header_hex).map_err(Error::DecodeHex)
+    }
+
+    /// Retrieves the `Txid`s for all transactions in the mempool.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `Txid`s in the raw mempool
+    pub async fn get_raw_mempool(&self) -> Result<Vec<Txid>, Error> {
+        self.call::<GetRawMempool>("getrawmempool", &[])
+            .await
+            .map(|txids| txids.0)
+    }
+
+    /// Retrieves the raw transaction data for a given transaction ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `txid`: The transaction ID to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The deserialized `Transaction` struct
+    pub async fn get_raw_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
+        let tx_hex: String = self.call("getrawtransaction", &[json!(txid)]).await?;
+        deserialize_hex(&tx_hex).map_err(Error::DecodeHex)
+    }
+
+    /// Retrieves a transaction with the block it is confirmed in, if any (verbosity 1).
+    ///
+    /// Without `-txindex`, only mempool transactions are found, unless the block containing the
+    /// transaction is given. The response schema is the same on all supported Bitcoin Core
+    /// versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `txid`: The transaction ID to retrieve.
+    /// * `block_hash`: The hash of the block to look for the transaction in, if known.
+    ///
+    /// # Returns
+    ///
+    /// The transaction, and the hash, time and number of confirmations of its block, which are
+    /// `None` for mempool transactions, as a `GetRawTransactionVerbose` struct.
+    /// `in_active_chain` is only set if `block_hash` is given, and is `false` if that block is not
+    /// in the best chain, e.g. because it was reorganized out.
+    pub async fn get_raw_transaction_info(
+        &self,
+        txid: &Txid,
+        block_hash: Option<&BlockHash>,
+    ) -> Result<GetRawTransactionVerbose, Error> {
+        let args = [json!(txid), json!(1), json!(block_hash)];
+        self.call::<v30::GetRawTransactionVerbose>("getrawtransaction", &args)
+            .await?
+            .into_model()
+            .map_err(Error::GetRawTransactionVerbose)
+    }
+
+    /// Retrieves the verbose JSON representation of a block header (verbosity 1).
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The verbose header as a `GetBlockHeaderVerbose` struct.
+    pub async fn get_block_header_verbose(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<GetBlockHeaderVerbose, Error> {
+        let args = [json!(block_hash)];
+        match self.server_version().await? {
+            CoreVersion::V28 => self
+                .call::<v28::GetBlockHeaderVerbose>("getblockheader", &args)
+                .await?
+                .into_model()
+                .map_err(Error::GetBlockHeaderVerboseV28),
+            _ => self
+                .call::<v30::GetBlockHeaderVerbose>("getblockheader", &args)
+                .await?
+                .into_model()
+                .map_err(Error::GetBlockHeaderVerbose),
+        }
+    }
+
+    /// Retrieves the verbose JSON representation of a block (verbosity 1).
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The verbose block data as a `GetBlockVerboseOne` struct.
+    pub async fn get_block_verbose(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<GetBlockVerboseOne, Error> {
+        let args = [json!(block_hash), json!(1)];
+        match self.server_version().await? {
+            CoreVersion::V28 => self
+                .call::<v28::GetBlockVerboseOne>("getblock", &args)
+                .await?
+                .into_model()
+                .map_err(Error::GetBlockVerboseOneV28),
+            _ => self
+                .call::<v30::GetBlockVerboseOne>("getblock", &args)
+                .await?
+                .into_model()
+                .map_err(Error::GetBlockVerboseOne),
+        }
+    }
+
+    /// Retrieves the state of the best chain, such as the network, the tip, the verification
+    /// progress and whether the node is in initial block download or pruned.
+    ///
+    /// # Returns
+    ///
+    /// The chain state as a `GetBlockchainInfo` struct. `bits`, `target` and `signet_challenge`
+    /// are `None`, and `softforks` is populated, on Bitcoin Core v28 only.
+    pub async fn get_blockchain_info(&self) -> Result<GetBlockchainInfo, Error> {
+        match self.server_version().await? {
+            CoreVersion::V28 => self
+                .call::<v28::GetBlockchainInfo>("getblockchaininfo", &[])
+                .await?
+                .into_model()
+                .map_err(Error::GetBlockchainInfoV28),
+            _ => self
+                .call::<v30::GetBlockchainInfo>("getblockchaininfo", &[])
+                .await?
+                .into_model()
+                .map_err(Error::GetBlockchainInfo),
+        }
+    }
+
+    /// Retrieves the tips of all the chains known to the node, including the best chain and
+    /// orphaned branches.
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions.
+    ///
+    /// # Returns
+    ///
+    /// The height, hash, branch length and status of each tip, as a `GetChainTips` struct.
+    pub async fn get_chain_tips(&self) -> Result<GetChainTips, Error> {
+        self.call::<v30::GetChainTips>("getchaintips", &[])
+            .await?
+            .into_model()
+            .map_err(Error::GetChainTips)
+    }
+
+    /// Retrieves statistics about the total number and rate of transactions in the chain.
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `nblocks`: The size of the window, in blocks. Defaults to one month of blocks.
+    /// * `block_hash`: The hash of the block ending the window. Defaults to the tip.
+    ///
+    /// # Returns
+    ///
+    /// The transaction count and rate over the window, as a `GetChainTxStats` struct.
+    pub async fn get_chain_tx_stats(
+        &self,
+        nblocks: Option<u32>,
+        block_hash: Option<&BlockHash>,
+    ) -> Result<GetChainTxStats, Error> {
+        let args = [json!(nblocks), json!(block_hash)];
+        self.call::<v30::GetChainTxStats>("getchaintxstats", &args)
+            .await?
+            .into_model()
+            .map_err(Error::GetChainTxStats)
+    }
+
+    /// Retrieves the height of the lowest block whose data is stored by the node.
+    ///
+    /// # Returns
+    ///
+    /// The prune height, or `None` if the node is not pruned.
+    pub async fn prune_height(&self) -> Result<Option<u32>, Error> {
+        let info = self.get_blockchain_info().await?;
+        Ok(info.prune_height.filter(|_| info.pruned))
+    }
+
+    /// Requests the block with the given hash from a peer, e.g. to fetch a pruned block again.
+    ///
+    /// The request is asynchronous: the block is available once the peer sent it.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block, whose header must be known to the node.
+    /// * `peer_id`: The index of the peer, as returned by `getpeerinfo`.
+    pub async fn get_block_from_peer(
+        &self,
+        block_hash: &BlockHash,
+        peer_id: u64,
+    ) -> Result<(), Error> {
+        self.call::<serde_json::Value>("getblockfrompeer", &[json!(block_hash), json!(peer_id)])
+            .await
+            .map(|_| ())
+    }
+
+    /// Creates and loads a new descriptor wallet.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the new wallet.
+    ///
+    /// # Returns
+    ///
+    /// The name of the created wallet and any warnings, as a `CreateWallet` struct.
+    pub async fn create_wallet(&self, name: &str) -> Result<CreateWallet, Error> {
+        self.call::<v30::CreateWallet>("createwallet", &[json!(name)])
+            .await
+            .map(|wallet| wallet.into_model())
+    }
+
+    /// Loads the wallet `name` from the wallet directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the wallet to load.
+    ///
+    /// # Returns
+    ///
+    /// The name of the loaded wallet and any warnings, as a `LoadWallet` struct.
+    pub async fn load_wallet(&self, name: &str) -> Result<LoadWallet, Error> {
+        self.call::<v30::LoadWallet>("loadwallet", &[json!(name)])
+            .await
+            .map(|wallet| wallet.into_model())
+    }
+
+    /// Unloads the wallet `name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the wallet to unload.
+    ///
+    /// # Returns
+    ///
+    /// Any warnings, as an `UnloadWallet` struct.
+    pub async fn unload_wallet(&self, name: &str) -> Result<UnloadWallet, Error> {
+        self.call::<v30::UnloadWallet>("unloadwallet", &[json!(name)])
+            .await
+            .map(|wallet| wallet.into_model())
+    }
+
+    /// Retrieves the names of the loaded wallets.
+    ///
+    /// # Returns
+    ///
+    /// A vector of wallet names.
+    pub async fn list_wallets(&self) -> Result<Vec<String>, Error> {
+        self.call::<ListWallets>("listwallets", &[])
+            .await
+            .map(|wallets| wallets.0)
+    }
+
+    /// Waits for a new block to be connected to the best chain.
+    ///
+    /// The request timeout is extended by `timeout`, so the long poll is not aborted by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout`: How long to wait, with millisecond granularity. `bitcoind` waits indefinitely
+    ///   if zero, but the request is still aborted after the client's request timeout.
+    ///
+    /// # Returns
+    ///
+    /// The hash and height of the new tip, or of the current tip if the timeout expired first.
+    pub async fn wait_for_new_block(&self, timeout: Duration) -> Result<(BlockHash, u32), Error> {
+        let args = [json!(timeout_millis(timeout))];
+        let block = self
+            .call_with_timeout::<v30::WaitForNewBlock>("waitfornewblock", &args, timeout)
+            .await?
+            .into_model()
+            .map_err(Error::WaitForNewBlock)?;
+        Ok((block.hash, block.height))
+    }
+
+    /// Waits for the block with the given hash to be connected to the best chain.
+    ///
+    /// The request timeout is extended by `timeout`, so the long poll is not aborted by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to wait for.
+    /// * `timeout`: How long to wait, with millisecond granularity. `bitcoind` waits indefinitely
+    ///   if zero, but the request is still aborted after the client's request timeout.
+    ///
+    /// # Returns
+    ///
+    /// The hash and height of the tip, which is not `block_hash` if the timeout expired first.
+    pub async fn wait_for_block(
+        &self,
+        block_hash: &BlockHash,
+        timeout: Duration,
+    ) -> Result<(BlockHash, u32), Error> {
+        let args = [json!(block_hash), json!(timeout_millis(timeout))];
+        let block = self
+            .call_with_timeout::<v30::WaitForBlock>("waitforblock", &args, timeout)
+            .await?
+            .into_model()
+            .map_err(Error::WaitForBlock)?;
+        Ok((block.hash, block.height))
+    }
+
+    /// Waits for the best chain to reach `height`.
+    ///
+    /// The request timeout is extended by `timeout`, so the long poll is not aborted by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `height`: The height to wait for.
+    /// * `timeout`: How long to wait, with millisecond granularity. `bitcoind` waits indefinitely
+    ///   if zero, but the request is still aborted after the client's request timeout.
+    ///
+    /// # Returns
+    ///
+    /// The hash and height of the tip, which is below `height` if the timeout expired first.
+    pub async fn wait_for_block_height(
+        &self,
+        height: u32,
+        timeout: Duration,
+    ) -> Result<(BlockHash, u32), Error> {
+        let args = [json!(height), json!(timeout_millis(timeout))];
+        let block = self
+            .call_with_timeout::<v30::WaitForBlockHeight>("waitforblockheight", &args, timeout)
+            .await?
+            .into_model()
+            .map_err(Error::WaitForBlockHeight)?;
+        Ok((block.hash, block.height))
+    }
+
+    /// Retrieves the ZMQ notifications published by the node, configured with
+    /// `-zmqpub<topic>=<address>`.
+    ///
+    /// # Returns
+    ///
+    /// The type, e.g. `pubhashblock`, address and high water mark of each notification. Empty if
+    /// ZMQ notifications are disabled.
+    pub async fn get_zmq_notifications(&self) -> Result<Vec<GetZmqNotifications>, Error> {
+        self.call("getzmqnotifications", &[]).await
+    }
+
+    /// Submits a raw transaction to the node, which validates it and relays it to its peers.
+    ///
+    /// Uses the default `bitcoind` safety limits, see [`AsyncClient::send_raw_transaction_with_options`].
+    ///
+    /// # Arguments
+    ///
+    /// * `tx`: The transaction to broadcast.
+    ///
+    /// # Returns
+    ///
+    /// The `Txid` of the broadcast transaction.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::TxRejected` if the transaction was rejected, e.g. by mempool policy.
+    pub async fn send_raw_transaction(&self, tx: &Transaction) -> Result<Txid, Error> {
+        self.send_raw_transaction_with_options(tx, BroadcastOptions::default())
+            .await
+    }
+
+    /// Submits a raw transaction to the node, rejecting it if it exceeds the limits in `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx`: The transaction to broadcast.
+    /// * `options`: The maximum fee rate and burn amount accepted.
+    ///
+    /// # Returns
+    ///
+    /// The `Txid` of the broadcast transaction.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::TxRejected` if the transaction was rejected, e.g. by mempool policy.
+    pub async fn send_raw_transaction_with_options(
+        &self,
+        tx: &Transaction,
+        options: BroadcastOptions,
+    ) -> Result<Txid, Error> {
+        let [max_fee_rate, max_burn_amount] = options.to_args();
+        let args = [json!(serialize_hex(tx)), max_fee_rate, max_burn_amount];
+        self.call::<v30::SendRawTransaction>("sendrawtransaction", &args)
+            .await
+            .map_err(|e| TxRejection::from_error(tx.compute_txid(), e))?
+            .txid()
+            .map_err(Error::HexToArray)
+    }
+
+    /// Checks whether raw transactions would be accepted by the mempool, without submitting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `txs`: The transactions to test. If more than one, they must form a package, where
+    ///   children come after their parents.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `MempoolAcceptance`, one per transaction, holding either its vsize and fees, or
+    /// its reject reason. See [`RejectReason::from_message`](crate::RejectReason::from_message) to
+    /// classify the reject reason.
+    pub async fn test_mempool_accept(
+        &self,
+        txs: &[Transaction],
+    ) -> Result<Vec<MempoolAcceptance>, Error> {
+        let raw_txs = txs.iter().map(serialize_hex).collect::<Vec<_>>();
+        self.call::<v30::TestMempoolAccept>("testmempoolaccept", &[json!(raw_txs)])
+            .await?
+            .into_model()
+            .map(|accept| accept.results)
+            .map_err(Error::TestMempoolAccept)
+    }
+
+    /// Submits a package of raw transactions to the node, which validates them together, e.g. so a
+    /// child can pay for a parent below the mempool minimum fee (CPFP).
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `txs`: The package, made of a child and its parents, sorted so that the child comes last.
+    /// * `options`: The maximum fee rate and burn amount accepted.
+    ///
+    /// # Returns
+    ///
+    /// The `SubmitPackage` result, with the per-transaction results keyed by `Wtxid`, their
+    /// effective fee rates and the txids of the transactions replaced by the package.
+    pub async fn submit_package(
+        &self,
+        txs: &[Transaction],
+        options: BroadcastOptions,
+    ) -> Result<SubmitPackage, Error> {
+        let raw_txs = txs.iter().map(serialize_hex).collect::<Vec<_>>();
+        let [max_fee_rate, max_burn_amount] = options.to_args();
+        let args = [json!(raw_txs), max_fee_rate, max_burn_amount];
+        self.call::<v30::SubmitPackage>("submitpackage", &args)
+            .await?
+            .into_model()
+            .map_err(Error::SubmitPackage)
+    }
+
+    /// Estimates the fee rate needed for a transaction to confirm within `conf_target` blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `conf_target`: The confirmation target in blocks, between 1 and 1008.
+    /// * `mode`: The estimate mode.
+    ///
+    /// # Returns
+    ///
+    /// The estimated `FeeRate`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::FeeEstimateUnavailable` with the errors reported by `bitcoind` if there is
+    ///   no estimate, e.g. because the node has not seen enough transactions confirm yet.
+    pub async fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        mode: EstimateMode,
+    ) -> Result<FeeRate, Error> {
+        let args = [json!(conf_target), json!(mode.as_str())];
+        fees::into_fee_rate(self.call("estimatesmartfee", &args).await?)
+    }
+
+    /// Estimates the fee rates needed to confirm within each of the `targets`. See
+    /// [`FEE_TABLE_TARGETS`](crate::FEE_TABLE_TARGETS) for a typical set of targets.
+    ///
+    /// Unlike [`Client::estimate_fee_table`](crate::Client::estimate_fee_table), which sends a
+    /// single batch request, the targets are estimated one request at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets`: The confirmation targets in blocks.
+    /// * `mode`: The estimate mode.
+    ///
+    /// # Returns
+    ///
+    /// The estimated `FeeRate` of each target. Targets without an estimate are left out.
+    pub async fn estimate_fee_table(
+        &self,
+        targets: &[u16],
+        mode: EstimateMode,
+    ) -> Result<BTreeMap<u16, FeeRate>, Error> {
+        let mut table = BTreeMap::new();
+        for target in targets {
+            match self.estimate_smart_fee(*target, mode).await {
+                Ok(fee_rate) => {
+                    table.insert(*target, fee_rate);
+                }
+                Err(Error::FeeEstimateUnavailable(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(table)
+    }
+}
@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! JSON-RPC batch requests.
+//!
+//! A [`Batch`] queues several calls and sends them to `bitcoind` as a single JSON-RPC array,
+//! saving one HTTP round-trip per call. Each queued call returns a typed [`BatchCall`] handle
+//! that is later used to extract its result from the [`BatchResponse`].
+
+use corepc_types::{
+    bitcoin::{
+        Block, BlockHash, FeeRate, Transaction, Txid,
+        block::Header,
+        consensus::encode::{deserialize, deserialize_hex},
+    },
+    model::{GetBlockCount, GetBlockFilter},
+    v30,
+};
+use jsonrpc::{
+    Response, serde,
+    serde_json::{self, json},
+};
+
+use crate::client::HexBytes;
+use crate::fees::{self, EstimateMode};
+use crate::{Client, Error};
+
+/// A call queued in a [`Batch`], used to retrieve its typed result from a [`BatchResponse`].
+#[derive(Debug)]
+pub struct BatchCall<T> {
+    /// Position of the call in the batch.
+    index: usize,
+    /// Converts the raw JSON-RPC response into the typed result.
+    parse: fn(&Response) -> Result<T, Error>,
+}
+
+/// A builder that queues JSON-RPC calls to be sent in a single request.
+///
+/// Created with [`Client::batch`].
+#[derive(Debug)]
+pub struct Batch<'a> {
+    /// The client used to send the batch.
+    client: &'a Client,
+    /// The queued method names and their arguments.
+    calls: Vec<(String, serde_json::Value)>,
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Returns the number of calls queued in this batch.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Returns `true` if no calls have been queued.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    fn push<T>(
+        &mut self,
+        method: &str,
+        args: Vec<serde_json::Value>,
+        parse: fn(&Response) -> Result<T, Error>,
+    ) -> BatchCall<T> {
+        let index = self.calls.len();
+        self.calls.push((method.to_owned(), args.into()));
+        BatchCall { index, parse }
+    }
+
+    /// Queues a call to the RPC `method` with the given `args`.
+    ///
+    /// This is the generic function used by all specific batched RPC methods.
+    pub fn call<T>(&mut self, method: &str, args: &[serde_json::Value]) -> BatchCall<T>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        self.push(method, args.to_vec(), |resp| Ok(resp.result()?))
+    }
+
+    /// Queues a `getblock` call (verbosity 0).
+    ///
+    /// Like [`Client::get_block`], a malformed block fails with `Error::Decode`.
+    pub fn get_block(&mut self, block_hash: &BlockHash) -> BatchCall<Block> {
+        self.push("getblock", vec![json!(block_hash), json!(0)], |resp| {
+            deserialize(&resp.result::<HexBytes>()?.0).map_err(Error::Decode)
+        })
+    }
+
+    /// Queues a `getbestblockhash` call.
+    pub fn get_best_block_hash(&mut self) -> BatchCall<BlockHash> {
+        self.push("getbestblockhash", vec![], |resp| {
+            resp.result::<String>()?.parse().map_err(Error::HexToArray)
+        })
+    }
+
+    /// Queues a `getblockcount` call.
+    pub fn get_block_count(&mut self) -> BatchCall<u32> {
+        self.push("getblockcount", vec![], |resp| {
+            resp.result::<GetBlockCount>()?
+                .0
+                .try_into()
+                .map_err(Error::TryFromInt)
+        })
+    }
+
+    /// Queues a `getblockhash` call for the block at `height`.
+    pub fn get_block_hash(&mut self, height: u32) -> BatchCall<BlockHash> {
+        self.push("getblockhash", vec![json!(height)], |resp| {
+            resp.result::<String>()?.parse().map_err(Error::HexToArray)
+        })
+    }
+
+    /// Queues a `getblockfilter` call for the block with `block_hash`.
+    pub fn get_block_filter(&mut self, block_hash: &BlockHash) -> BatchCall<GetBlockFilter> {
+        self.push("getblockfilter", vec![json!(block_hash)], |resp| {
+            resp.result::<v30::GetBlockFilter>()?
+                .into_model()
+                .map_err(Error::GetBlockFilter)
+        })
+    }
+
+    /// Queues a `getblockheader` call (non-verbose) for the block with `block_hash`.
+    pub fn get_block_header(&mut self, block_hash: &BlockHash) -> BatchCall<Header> {
+        self.push(
+            "getblockheader",
+            vec![json!(block_hash), json!(false)],
+            |resp| deserialize_hex(&resp.result::<String>()?).map_err(Error::DecodeHex),
+        )
+    }
+
+    /// Queues a `getrawtransaction` call for the transaction with `txid`.
+    pub fn get_raw_transaction(&mut self, txid: &Txid) -> BatchCall<Transaction> {
+        self.push("getrawtransaction", vec![json!(txid)], |resp| {
+            deserialize_hex(&resp.result::<String>()?).map_err(Error::DecodeHex)
+        })
+    }
+
+    /// Queues an `estimatesmartfee` call for confirmation within `conf_target` blocks.
+    pub fn estimate_smart_fee(
+        &mut self,
+        conf_target: u16,
+        mode: EstimateMode,
+    ) -> BatchCall<FeeRate> {
+        self.push(
+            "estimatesmartfee",
+            vec![json!(conf_target), json!(mode.as_str())],
+            |resp| fees::into_fee_rate(resp.result()?),
+        )
+    }
+
+    /// Sends all queued calls in a single JSON-RPC batch request.
+    ///
+    /// Only transport-level failures are returned here. Errors returned by `bitcoind` for an
+    /// individual call are reported by [`BatchResponse::get`] for that call alone, so a single
+    /// failing call does not affect the others.
+    pub fn send(self) -> Result<BatchResponse, Error> {
+        if self.calls.is_empty() {
+            return Ok(BatchResponse {
+                responses: Vec::new(),
+            });
+        }
+
+        let params = self
+            .calls
+            .iter()
+            .map(|(_, args)| serde_json::value::to_raw_value(args))
+            .collect::<Result<Vec<_>, _>>()?;
+        let requests = self
+            .calls
+            .iter()
+            .zip(&params)
+            .map(|((method, _), args)| self.client.inner.build_request(method, Some(args)))
+            .collect::<Vec<_>>();
+
+        let responses = self.client.inner.send_batch(&requests)?;
+
+        Ok(BatchResponse { responses })
+    }
+}
+
+/// The responses to a [`Batch`] sent with [`Batch::send`].
+#[derive(Debug)]
+pub struct BatchResponse {
+    /// The response for the call at the corresponding index, if the server provided one.
+    responses: Vec<Option<Response>>,
+}
+
+impl BatchResponse {
+    /// Returns the typed result of `call`.
+    ///
+    /// `call` must have been queued in the [`Batch`] that produced this response.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::MissingBatchResponse` if the server did not answer this call.
+    /// * Returns the error produced by `bitcoind` for this call, or the error converting its
+    ///   result into `T`.
+    pub fn get<T>(&self, call: &BatchCall<T>) -> Result<T, Error> {
+        match self.responses.get(call.index) {
+            Some(Some(resp)) => (call.parse)(resp),
+            _ => Err(Error::MissingBatchResponse),
+        }
+    }
+
+    /// Returns the number of calls in the batch.
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns `true` if the batch was empty.
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Types used to broadcast transactions and report why `bitcoind` rejected them.
+
+use core::fmt;
+
+use corepc_types::bitcoin::{Amount, FeeRate, Txid};
+use jsonrpc::serde_json::{Value, json};
+
+use crate::error::{Error, RpcErrorCode};
+
+/// Safety limits checked by `bitcoind` before broadcasting a transaction.
+///
+/// Fields left to `None` use the `bitcoind` defaults: a maximum fee rate of 0.10 BTC/kvB, and no
+/// unspendable outputs with a non-zero amount.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadcastOptions {
+    /// Reject transactions paying a higher fee rate. A zero fee rate disables the check.
+    pub max_fee_rate: Option<FeeRate>,
+    /// Reject transactions with unspendable outputs (e.g. `OP_RETURN`) worth more than this.
+    pub max_burn_amount: Option<Amount>,
+}
+
+impl BroadcastOptions {
+    /// Returns the `maxfeerate` and `maxburnamount` arguments of `sendrawtransaction`.
+    pub(crate) fn to_args(self) -> [Value; 2] {
+        // `bitcoind` expects the fee rate in BTC/kvB. Saturate so huge fee rates are rejected by
+        // `bitcoind` instead of overflowing.
+        let max_fee_rate = self
+            .max_fee_rate
+            .map(|rate| Amount::from_sat(rate.to_sat_per_kwu().saturating_mul(4)).to_btc());
+        let max_burn_amount = self.max_burn_amount.map(|amount| amount.to_btc());
+        [json!(max_fee_rate), json!(max_burn_amount)]
+    }
+}
+
+/// The category of the reason why `bitcoind` rejected a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RejectReason {
+    /// The transaction is already confirmed.
+    AlreadyInChain,
+    /// The transaction is already in the mempool.
+    AlreadyInMempool,
+    /// An input is missing or already spent.
+    MissingInputs,
+    /// The fee is below the minimum relay fee, the mempool minimum fee, or the fee needed to
+    /// replace a conflicting transaction.
+    InsufficientFee,
+    /// The transaction conflicts with a mempool transaction it can't replace.
+    MempoolConflict,
+    /// The fee rate exceeds [`BroadcastOptions::max_fee_rate`].
+    MaxFeeExceeded,
+    /// An unspendable output exceeds [`BroadcastOptions::max_burn_amount`].
+    MaxBurnExceeded,
+    /// Any other consensus or policy violation, see [`TxRejection::message`].
+    Other,
+}
+
+impl RejectReason {
+    /// Classifies a reject reason reported by `bitcoind`, e.g. the `reject-reason` of
+    /// `testmempoolaccept` or the error message of `sendrawtransaction`.
+    pub fn from_message(message: &str) -> Self {
+        const REASONS: &[(&str, RejectReason)] = &[
+            ("already in utxo set", RejectReason::AlreadyInChain),
+            ("already in block chain", RejectReason::AlreadyInChain),
+            ("txn-already-in-mempool", RejectReason::AlreadyInMempool),
+            ("txn-already-known", RejectReason::AlreadyInMempool),
+            ("missing inputs", RejectReason::MissingInputs),
+            ("missingorspent", RejectReason::MissingInputs),
+            ("min relay fee not met", RejectReason::InsufficientFee),
+            ("mempool min fee not met", RejectReason::InsufficientFee),
+            ("insufficient fee", RejectReason::InsufficientFee),
+            ("txn-mempool-conflict", RejectReason::MempoolConflict),
+            ("max-fee-exceeded", RejectReason::MaxFeeExceeded),
+            ("fee exceeds maximum", RejectReason::MaxFeeExceeded),
+            ("maxburnamount", RejectReason::MaxBurnExceeded),
+        ];
+        let message = message.to_lowercase();
+        REASONS
+            .iter()
+            .find(|(pattern, _)| message.contains(pattern))
+            .map_or(RejectReason::Other, |(_, reason)| *reason)
+    }
+}
+
+/// A transaction rejected by `bitcoind`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxRejection {
+    /// ID of the rejected transaction.
+    pub txid: Txid,
+    /// The category of the reject reason.
+    pub reason: RejectReason,
+    /// The reject reason as reported by `bitcoind`, e.g. `min relay fee not met, 100 < 110`.
+    pub message: String,
+}
+
+impl TxRejection {
+    /// Converts `error` into a [`TxRejection`] of the transaction `txid` if `bitcoind` refused to
+    /// accept the transaction, or returns it unchanged otherwise.
+    pub(crate) fn from_error(txid: Txid, error: Error) -> Error {
+        let reason = match error.rpc_error_code() {
+            Some(RpcErrorCode::VerifyAlreadyInChain) => RejectReason::AlreadyInChain,
+            Some(RpcErrorCode::VerifyError | RpcErrorCode::VerifyRejected) => {
+                RejectReason::from_message(error.rpc_error_message().unwrap_or_default())
+            }
+            _ => return error,
+        };
+        let message = error.rpc_error_message().unwrap_or_default().to_owned();
+
+        Error::TxRejected(TxRejection {
+            txid,
+            reason,
+            message,
+        })
+    }
+}
+
+impl fmt::Display for TxRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "transaction {} rejected: {}", self.txid, self.message)
+    }
+}
+
+#[cfg(test)]
+mod test_broadcast {
+    use super::*;
+
+    #[test]
+    fn test_reject_reason_from_message() {
+        let cases = [
+            (
+                "Transaction outputs already in utxo set",
+                RejectReason::AlreadyInChain,
+            ),
+            ("txn-already-in-mempool", RejectReason::AlreadyInMempool),
+            (
+                "bad-txns-inputs-missingorspent",
+                RejectReason::MissingInputs,
+            ),
+            (
+                "min relay fee not met, 0 < 110",
+                RejectReason::InsufficientFee,
+            ),
+            (
+                "insufficient fee, rejecting replacement",
+                RejectReason::InsufficientFee,
+            ),
+            ("txn-mempool-conflict", RejectReason::MempoolConflict),
+            ("max-fee-exceeded", RejectReason::MaxFeeExceeded),
+            (
+                "Fee exceeds maximum configured by user (e.g. -maxtxfee, maxfeerate)",
+                RejectReason::MaxFeeExceeded,
+            ),
+            (
+                "Unspendable output exceeds maximum configured by user (maxburnamount)",
+                RejectReason::MaxBurnExceeded,
+            ),
+            ("dust", RejectReason::Other),
+        ];
+        for (message, reason) in cases {
+            assert_eq!(RejectReason::from_message(message), reason, "{message}");
+        }
+    }
+
+    #[test]
+    fn test_from_error() {
+        let txid = "0101010101010101010101010101010101010101010101010101010101010101"
+            .parse::<Txid>()
+            .unwrap();
+        let rpc_error = |code: i32, message: &str| {
+            Error::JsonRpc(jsonrpc::Error::Rpc(jsonrpc::error::RpcError {
+                code,
+                message: message.to_owned(),
+                data: None,
+            }))
+        };
+
+        let err = TxRejection::from_error(txid, rpc_error(-26, "min relay fee not met, 0 < 110"));
+        let Error::TxRejected(rejection) = err else {
+            panic!("expected a rejection, got {err:?}");
+        };
+        assert_eq!(rejection.txid, txid);
+        assert_eq!(rejection.reason, RejectReason::InsufficientFee);
+        assert_eq!(rejection.message, "min relay fee not met, 0 < 110");
+
+        let err = TxRejection::from_error(txid, rpc_error(-27, "already in chain"));
+        assert!(matches!(
+            err,
+            Error::TxRejected(TxRejection {
+                reason: RejectReason::AlreadyInChain,
+                ..
+            })
+        ));
+
+        let err = TxRejection::from_error(txid, rpc_error(-22, "TX decode failed"));
+        assert!(matches!(err, Error::JsonRpc(_)));
+    }
+
+    #[test]
+    fn test_options_to_args() {
+        assert_eq!(
+            BroadcastOptions::default().to_args(),
+            [Value::Null, Value::Null]
+        );
+
+        let options = BroadcastOptions {
+            max_fee_rate: Some(FeeRate::from_sat_per_vb_unchecked(10)),
+            max_burn_amount: Some(Amount::from_sat(1_000)),
+        };
+        // 10 sat/vB is 10,000 sat/kvB.
+        assert_eq!(options.to_args(), [json!(0.0001), json!(0.00001)]);
+
+        let options = BroadcastOptions {
+            max_fee_rate: Some(FeeRate::MAX),
+            max_burn_amount: None,
+        };
+        assert_eq!(
+            options.to_args(),
+            [json!(Amount::from_sat(u64::MAX).to_btc()), Value::Null]
+        );
+    }
+}
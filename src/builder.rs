@@ -0,0 +1,363 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Configurable construction of a [`Client`], or of an `AsyncClient` with the `async` feature.
+
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use corepc_types::bitcoin::Network;
+
+#[cfg(feature = "async")]
+use crate::AsyncClient;
+use crate::client::{Auth, Client};
+use crate::config::RpcConfig;
+use crate::error::Error;
+use crate::rest::RestClient;
+use crate::retry::RetryPolicy;
+use crate::transport::HttpTransport;
+
+/// The default request timeout.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A builder for a [`Client`] with a custom HTTP configuration.
+///
+/// With the `async` feature, the same configuration builds an `AsyncClient` with
+/// `ClientBuilder::build_async`.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use bdk_bitcoind_client::{Auth, ClientBuilder};
+/// # fn main() -> Result<(), bdk_bitcoind_client::Error> {
+/// let client = ClientBuilder::new("http://127.0.0.1:18443")
+///     .auth(Auth::UserPass("user".to_string(), "pass".to_string()))
+///     .timeout(Duration::from_secs(5))
+///     .wallet("watch-only")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    /// URL of the RPC server.
+    url: String,
+    /// Authentication method, if any.
+    auth: Option<Auth>,
+    /// Timeout applied to every request.
+    timeout: Duration,
+    /// Name of the wallet that wallet RPCs are sent to.
+    pub(crate) wallet: Option<String>,
+    /// How calls failing with transient errors are retried, if at all.
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    /// The value of the `User-Agent` HTTP header.
+    user_agent: Option<String>,
+    /// Maximum size of a response body in bytes.
+    max_response_size: Option<usize>,
+    /// Additional HTTP headers sent with every request.
+    headers: Vec<(String, String)>,
+    /// Whether blocks are fetched over the REST interface.
+    pub(crate) rest: bool,
+    /// The network the node must be on, verified when the client is built.
+    expected_network: Option<Network>,
+    /// How long to wait for pruned blocks requested from peers, if they are requested at all.
+    pub(crate) pruned_block_timeout: Option<Duration>,
+}
+
+impl ClientBuilder {
+    /// Creates a [`ClientBuilder`] for the RPC server at `url`, without authentication and with a
+    /// 60 seconds timeout.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            auth: None,
+            timeout: DEFAULT_TIMEOUT,
+            wallet: None,
+            retry_policy: None,
+            user_agent: None,
+            max_response_size: None,
+            headers: Vec::new(),
+            rest: false,
+            expected_network: None,
+            pruned_block_timeout: None,
+        }
+    }
+
+    /// Creates a [`ClientBuilder`] for the node running on `network`, with the URL and
+    /// authentication read from the `bitcoin.conf` in `datadir`.
+    ///
+    /// See [`RpcConfig::from_datadir`] for how the configuration is located and read.
+    pub fn from_datadir(network: Network, datadir: Option<&Path>) -> Result<Self, Error> {
+        let config = RpcConfig::from_datadir(network, datadir)?;
+        Ok(Self::new(&config.url).auth(config.auth))
+    }
+
+    /// Sets the authentication method (`UserPass` or `CookieFile`).
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Sets the timeout after which requests are aborted.
+    ///
+    /// The timeout has a granularity of one second, rounded up.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends requests to the `/wallet/<name>` endpoint, which selects the wallet used by wallet
+    /// RPCs when several wallets are loaded.
+    pub fn wallet(mut self, name: &str) -> Self {
+        self.wallet = Some(name.to_owned());
+        self
+    }
+
+    /// Retries calls failing with transient errors according to `policy`.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Sets the `User-Agent` HTTP header.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    /// Fails requests whose response body is larger than `max_response_size` bytes.
+    ///
+    /// By default, the response size is unlimited.
+    pub fn max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+
+    /// Adds an HTTP header sent with every request.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Fetches blocks over the REST interface, which transfers them in binary rather than hex.
+    ///
+    /// Requires `bitcoind` to run with `-rest=1`, otherwise blocks are fetched over JSON-RPC.
+    pub fn rest(mut self, enabled: bool) -> Self {
+        self.rest = enabled;
+        self
+    }
+
+    /// Downloads pruned blocks again from a peer with `getblockfrompeer`, waiting up to `timeout`
+    /// for them to arrive.
+    ///
+    /// By default, fetching a pruned block fails with `Error::BlockPruned`. The block is requested
+    /// from each connected peer in turn until one accepts the request, which fails for pruned
+    /// peers. The node deletes the block again on the next pruning.
+    pub fn fetch_pruned_blocks(mut self, timeout: Duration) -> Self {
+        self.pruned_block_timeout = Some(timeout);
+        self
+    }
+
+    /// Verifies that the node is on `network` when the client is built, see
+    /// [`Client::check_network`].
+    ///
+    /// Building the client then requires the node to be reachable.
+    pub fn expected_network(mut self, network: Network) -> Self {
+        self.expected_network = Some(network);
+        self
+    }
+
+    /// Builds the [`Client`].
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidUrl` if the URL is not an `http://` or `https://` URL.
+    /// * Returns `Error::NetworkMismatch` if an expected network is set and the node is on
+    ///   another network.
+    /// * Returns errors related to reading the cookie file.
+    /// * Returns errors from the `getblockchaininfo` and `getblockhash` RPC calls if an expected
+    ///   network is set.
+    pub fn build(self) -> Result<Client, Error> {
+        let network = self.expected_network;
+        let client = Client::from_builder(self)?;
+        if let Some(network) = network {
+            client.check_network(network)?;
+        }
+        Ok(client)
+    }
+
+    /// Builds an [`AsyncClient`] with the configuration of this builder.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidUrl` if the URL is not an `http://` or `https://` URL.
+    /// * Returns `Error::NetworkMismatch` if an expected network is set and the node is on
+    ///   another network.
+    /// * Returns errors related to reading the cookie file.
+    /// * Returns errors from the `getblockchaininfo` and `getblockhash` RPC calls if an expected
+    ///   network is set.
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<AsyncClient, Error> {
+        let network = self.expected_network;
+        let client = AsyncClient::from_builder(self)?;
+        if let Some(network) = network {
+            client.check_network(network).await?;
+        }
+        Ok(client)
+    }
+
+    /// Builds a [`RestClient`] for the REST interface of the server, with the timeout, maximum
+    /// response size and HTTP headers of this builder.
+    ///
+    /// The REST interface doesn't require authentication and is not wallet-specific, so the
+    /// authentication method and wallet are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidUrl` if the URL is not an `http://` or `https://` URL.
+    pub fn build_rest(self) -> Result<RestClient, Error> {
+        Ok(RestClient {
+            transport: HttpTransport {
+                url: self.base_url()?,
+                timeout: self.timeout,
+                authorization: RwLock::new(None),
+                cookie_file: None,
+                headers: self.headers(),
+                max_response_size: self.max_response_size,
+            },
+        })
+    }
+
+    /// Creates the HTTP transport described by this builder.
+    pub(crate) fn transport(&self) -> Result<HttpTransport, Error> {
+        let mut url = self.base_url()?;
+        if let Some(wallet) = &self.wallet {
+            url.push_str("/wallet/");
+            url.push_str(&percent_encode(wallet));
+        }
+
+        let authorization = match &self.auth {
+            Some(auth) => auth.basic_auth()?,
+            None => None,
+        };
+        let cookie_file = self
+            .auth
+            .as_ref()
+            .and_then(|auth| auth.cookie_file())
+            .map(|path| path.to_path_buf());
+
+        Ok(HttpTransport {
+            url,
+            timeout: self.timeout,
+            authorization: RwLock::new(authorization),
+            cookie_file,
+            headers: self.headers(),
+            max_response_size: self.max_response_size,
+        })
+    }
+
+    /// Creates the [`RestClient`] used by the [`Client`], if the REST interface is enabled.
+    pub(crate) fn rest_client(&self) -> Result<Option<RestClient>, Error> {
+        if !self.rest {
+            return Ok(None);
+        }
+        self.clone().build_rest().map(Some)
+    }
+
+    /// Returns the URL of the server, without a trailing slash.
+    fn base_url(&self) -> Result<String, Error> {
+        if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
+            return Err(Error::InvalidUrl(self.url.clone()));
+        }
+        Ok(self.url.trim_end_matches('/').to_owned())
+    }
+
+    /// Returns the HTTP headers sent with every request.
+    fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(user_agent) = &self.user_agent {
+            headers.push(("User-Agent".to_owned(), user_agent.clone()));
+        }
+        headers.extend(self.headers.iter().cloned());
+        headers
+    }
+}
+
+/// Percent-encodes everything but the unreserved URL characters, so `s` can be used as a path
+/// segment.
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test_builder {
+    use super::*;
+
+    #[test]
+    fn test_transport_url() {
+        let transport = ClientBuilder::new("http://127.0.0.1:18443/")
+            .transport()
+            .unwrap();
+        assert_eq!(transport.url, "http://127.0.0.1:18443");
+
+        let transport = ClientBuilder::new("http://127.0.0.1:18443")
+            .wallet("my wallet/1")
+            .transport()
+            .unwrap();
+        assert_eq!(
+            transport.url,
+            "http://127.0.0.1:18443/wallet/my%20wallet%2F1"
+        );
+    }
+
+    #[test]
+    fn test_transport_invalid_url() {
+        assert!(matches!(
+            ClientBuilder::new("127.0.0.1:18443").transport(),
+            Err(Error::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_rest_client() {
+        let builder = ClientBuilder::new("http://127.0.0.1:18443/")
+            .auth(Auth::UserPass("user".to_string(), "pass".to_string()))
+            .wallet("alice");
+        assert!(builder.rest_client().unwrap().is_none());
+
+        // The REST interface is not wallet-specific and doesn't require authentication.
+        let rest = builder.rest(true).rest_client().unwrap().unwrap();
+        assert_eq!(rest.transport.url, "http://127.0.0.1:18443");
+        assert_eq!(*rest.transport.authorization.read().unwrap(), None);
+    }
+
+    #[test]
+    fn test_transport_headers() {
+        let transport = ClientBuilder::new("http://127.0.0.1:18443")
+            .auth(Auth::UserPass("user".to_string(), "pass".to_string()))
+            .user_agent("bdk")
+            .header("X-Request-Source", "test")
+            .transport()
+            .unwrap();
+        assert_eq!(
+            *transport.authorization.read().unwrap(),
+            Some("Basic dXNlcjpwYXNz".to_owned())
+        );
+        assert_eq!(transport.cookie_file, None);
+        assert_eq!(
+            transport.headers,
+            vec![
+                ("User-Agent".to_owned(), "bdk".to_owned()),
+                ("X-Request-Source".to_owned(), "test".to_owned()),
+            ]
+        );
+    }
+}
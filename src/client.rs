@@ -1,28 +1,53 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{BufRead, BufReader},
-    path::PathBuf,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use crate::batch::Batch;
+use crate::broadcast::{BroadcastOptions, TxRejection};
+use crate::builder::ClientBuilder;
+use crate::download::BlockDownloader;
 use crate::error::Error;
-use crate::jsonrpc::bitreq_http::Builder;
+use crate::fees::{self, EstimateMode};
+use crate::prune::{self, PeerId};
+use crate::rest::{self, RestClient};
+use crate::retry::RetryPolicy;
+use crate::transport::{HttpTransport, SharedTransport};
+use crate::version::{CoreVersion, NetworkInfoVersion};
 use corepc_types::{
     bitcoin::{
-        Block, BlockHash, Transaction, Txid, block::Header, consensus::encode::deserialize_hex,
+        Block, BlockHash, FeeRate, Network, Transaction, Txid,
+        block::Header,
+        blockdata::constants::genesis_block,
+        consensus::encode::{deserialize, deserialize_hex, serialize_hex},
+        hex::FromHex,
+    },
+    model::{
+        CreateWallet, GetBlockCount, GetBlockFilter, GetBlockHeaderVerbose, GetBlockVerboseOne,
+        GetBlockchainInfo, GetChainTips, GetChainTxStats, GetRawMempool, GetRawTransactionVerbose,
+        ListWallets, LoadWallet, MempoolAcceptance, SubmitPackage, UnloadWallet,
     },
-    model::{GetBlockCount, GetBlockFilter, GetRawMempool},
-    v30,
+    v28,
+    v30::{self, GetZmqNotifications},
 };
 use jsonrpc::{
     Transport, serde,
     serde_json::{self, json},
 };
 
-#[cfg(feature = "28_0")]
-pub mod v28;
-
 /// Client authentication methods for the Bitcoin Core JSON-RPC server
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Auth {
@@ -52,6 +77,25 @@ impl Auth {
             }
         }
     }
+
+    /// Returns the value of the `Authorization` HTTP header for these credentials, if any.
+    ///
+    /// A `CookieFile` is read every time this is called.
+    pub(crate) fn basic_auth(&self) -> Result<Option<String>, Error> {
+        let (user, pass) = self.clone().get_user_pass()?;
+        Ok(user.map(|user| {
+            let credentials = format!("{user}:{}", pass.unwrap_or_default());
+            format!("Basic {}", BASE64.encode(credentials))
+        }))
+    }
+
+    /// Returns the path of the cookie file if authenticating with a `CookieFile`.
+    pub(crate) fn cookie_file(&self) -> Option<&Path> {
+        match self {
+            Auth::UserPass(..) => None,
+            Auth::CookieFile(path) => Some(path),
+        }
+    }
 }
 
 /// Bitcoin Core JSON-RPC Client.
@@ -60,14 +104,27 @@ impl Auth {
 #[derive(Debug)]
 pub struct Client {
     /// The inner JSON-RPC client.
-    inner: jsonrpc::Client,
+    pub(crate) inner: jsonrpc::Client,
+    /// The HTTP transport of the inner client, `None` for custom transports.
+    transport: Option<Arc<HttpTransport>>,
+    /// The server version, detected on first use.
+    version: OnceLock<CoreVersion>,
+    /// How calls failing with transient errors are retried, if at all.
+    retry_policy: Option<RetryPolicy>,
+    /// The configuration the client was built with, `None` for custom transports.
+    builder: Option<ClientBuilder>,
+    /// The client for the REST interface, if enabled.
+    rest: Option<RestClient>,
+    /// Whether the node turned out to serve blocks over JSON-RPC but not over REST.
+    rest_unavailable: AtomicBool,
 }
 
 impl Client {
     /// Creates a client connection to a bitcoind JSON-RPC server with authentication.
     ///
     /// Requires authentication via username/password or cookie file.
-    /// For connections without authentication, use `with_transport` instead.
+    /// For connections without authentication or with a custom configuration, use
+    /// [`Client::builder`] instead.
     ///
     /// # Arguments
     ///
@@ -79,19 +136,26 @@ impl Client {
     /// * Returns `Error::InvalidUrl` if the URL is invalid.
     /// * Returns errors related to reading the cookie file.
     pub fn with_auth(url: &str, auth: Auth) -> Result<Self, Error> {
-        let mut builder = Builder::new()
-            .url(url)
-            .map_err(|e| Error::InvalidUrl(format!("{e}")))?
-            .timeout(std::time::Duration::from_secs(60));
+        ClientBuilder::new(url).auth(auth).build()
+    }
 
-        let (user, pass) = auth.get_user_pass()?;
+    /// Creates a client connection to the node running on `network`, with the URL and
+    /// authentication read from the `bitcoin.conf` in `datadir`, or the default data directory.
+    ///
+    /// See [`RpcConfig::from_datadir`](crate::RpcConfig::from_datadir) for how the configuration
+    /// is located and read.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if the configuration can't be located or is invalid.
+    /// * Returns errors related to reading `bitcoin.conf` or the cookie file.
+    pub fn from_datadir(network: Network, datadir: Option<&Path>) -> Result<Self, Error> {
+        ClientBuilder::from_datadir(network, datadir)?.build()
+    }
 
-        if let Some(username) = user {
-            builder = builder.basic_auth(username, pass);
-        }
-        Ok(Self {
-            inner: jsonrpc::Client::with_transport(builder.build()),
-        })
+    /// Creates a [`ClientBuilder`] to configure a client connection to the RPC server at `url`.
+    pub fn builder(url: &str) -> ClientBuilder {
+        ClientBuilder::new(url)
     }
 
     /// Creates a client to a bitcoind JSON-RPC server with transport.
@@ -101,21 +165,188 @@ impl Client {
     {
         Self {
             inner: jsonrpc::Client::with_transport(transport),
+            transport: None,
+            version: OnceLock::new(),
+            retry_policy: None,
+            builder: None,
+            rest: None,
+            rest_unavailable: AtomicBool::new(false),
         }
     }
 
+    /// Creates a client with the HTTP transport and retry policy configured in `builder`.
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Result<Self, Error> {
+        let transport = Arc::new(builder.transport()?);
+        Ok(Self {
+            inner: jsonrpc::Client::with_transport(SharedTransport(Arc::clone(&transport))),
+            transport: Some(transport),
+            version: OnceLock::new(),
+            retry_policy: builder.retry_policy.clone(),
+            rest: builder.rest_client()?,
+            rest_unavailable: AtomicBool::new(false),
+            builder: Some(builder),
+        })
+    }
+
+    /// Returns a client for the wallet `name`, sharing the URL, authentication and configuration of
+    /// this client.
+    ///
+    /// Bitcoin Core routes wallet RPCs to the `/wallet/<name>` endpoint when several wallets are
+    /// loaded. The wallet must be loaded for calls to succeed, see [`Client::load_wallet`].
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::CustomTransport` if this client was created with
+    ///   [`Client::with_transport`].
+    /// * Returns errors related to reading the cookie file.
+    pub fn wallet(&self, name: &str) -> Result<Client, Error> {
+        let builder = self.builder.clone().ok_or(Error::CustomTransport)?;
+        self.with_builder(builder.wallet(name))
+    }
+
+    /// Returns a new client with the same configuration as this one, using its own connection.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::CustomTransport` if this client was created with
+    ///   [`Client::with_transport`].
+    pub(crate) fn try_clone(&self) -> Result<Client, Error> {
+        let builder = self.builder.clone().ok_or(Error::CustomTransport)?;
+        self.with_builder(builder)
+    }
+
+    /// Creates a client from `builder`, sharing the detected server version and the retry policy
+    /// of this client.
+    fn with_builder(&self, builder: ClientBuilder) -> Result<Client, Error> {
+        let mut client = Client::from_builder(builder)?;
+        client.retry_policy = self.retry_policy.clone();
+        if let Some(version) = self.version.get() {
+            let _ = client.version.set(*version);
+        }
+
+        Ok(client)
+    }
+
+    /// Retries calls failing with transient errors according to `policy`.
+    ///
+    /// By default, calls are not retried. Broadcasts are never retried, see [`RetryPolicy`].
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
     /// Calls the underlying RPC `method` with the given `args`.
     ///
-    /// This is the generic function used by all specific RPC methods.
+    /// This is the generic function used by all specific RPC methods. If a [`RetryPolicy`] is set,
+    /// calls failing with a retryable error are retried, blocking the current thread in between.
     pub fn call<T>(&self, method: &str, args: &[serde_json::Value]) -> Result<T, Error>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        self.call_with_timeout(method, args, Duration::ZERO)
+    }
+
+    /// Calls the RPC `method` with the given `args`, extending the request timeout by `timeout`.
+    ///
+    /// Used by long polls, which the node only answers after up to `timeout`. Clients created with
+    /// [`Client::with_transport`] keep the timeout of their transport.
+    fn call_with_timeout<T>(
+        &self,
+        method: &str,
+        args: &[serde_json::Value],
+        timeout: Duration,
+    ) -> Result<T, Error>
     where
         T: for<'de> serde::Deserialize<'de>,
     {
         let raw = serde_json::value::to_raw_value(args)?;
-        let request = self.inner.build_request(method, Some(&*raw));
-        let resp = self.inner.send_request(request)?;
+        let mut attempt = 1;
+        loop {
+            let request = self.inner.build_request(method, Some(&*raw));
+            let result = match &self.transport {
+                Some(transport) if !timeout.is_zero() => {
+                    let timeout = transport.timeout.saturating_add(timeout);
+                    transport.send_request_with_timeout(request, timeout)
+                }
+                _ => self.inner.send_request(request),
+            }
+            .and_then(|resp| resp.result())
+            .map_err(Error::from);
+            match (result, &self.retry_policy) {
+                (Err(e), Some(policy)) if policy.should_retry(method, attempt, &e) => {
+                    std::thread::sleep(policy.backoff(attempt));
+                    attempt += 1;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    /// Returns the [`CoreVersion`] of the server.
+    ///
+    /// The version is queried with `getnetworkinfo` on the first call and cached afterwards. It is
+    /// used to deserialize responses of RPC methods whose schema differs between releases.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::UnsupportedVersion` if the server is older than
+    ///   [`CoreVersion::MIN_SUPPORTED`].
+    pub fn server_version(&self) -> Result<CoreVersion, Error> {
+        if let Some(version) = self.version.get() {
+            return Ok(*version);
+        }
+        let info: NetworkInfoVersion = self.call("getnetworkinfo", &[])?;
+        let version = CoreVersion::from_server_version(info.version)?;
+
+        Ok(*self.version.get_or_init(|| version))
+    }
+
+    /// Verifies that the node is on `network`.
+    ///
+    /// Both the chain reported by `getblockchaininfo` and the hash of the genesis block are
+    /// checked, see [`ClientBuilder::expected_network`] to verify the network on construction.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::NetworkMismatch` if the node is on another network.
+    pub fn check_network(&self, network: Network) -> Result<(), Error> {
+        let info = self.get_blockchain_info()?;
+        let genesis_hash = self.get_block_hash(0)?;
+        verify_network(network, info.chain, genesis_hash)
+    }
+
+    /// Returns how long to wait for pruned blocks requested from peers, if they are requested.
+    fn pruned_block_timeout(&self) -> Option<Duration> {
+        self.builder.as_ref()?.pruned_block_timeout
+    }
 
-        Ok(resp.result()?)
+    /// Returns the name of the wallet this client sends requests to, if any.
+    pub fn wallet_name(&self) -> Option<&str> {
+        self.builder.as_ref()?.wallet.as_deref()
+    }
+
+    /// Creates a [`Batch`] to send several RPC calls in a single request.
+    pub fn batch(&self) -> Batch<'_> {
+        Batch::new(self)
+    }
+
+    /// Downloads the blocks at `heights` in parallel, using `workers` connections to the node.
+    ///
+    /// Each worker calls `getblockhash` and `getblock` for one height at a time, and the blocks
+    /// are yielded in height order by the returned [`BlockDownloader`]. The node serves at most
+    /// `-rpcthreads` requests at a time, so more workers than that don't speed up the download.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::CustomTransport` if this client was created with
+    ///   [`Client::with_transport`].
+    /// * Returns errors related to reading the cookie file.
+    pub fn fetch_blocks(
+        &self,
+        heights: RangeInclusive<u32>,
+        workers: usize,
+    ) -> Result<BlockDownloader, Error> {
+        BlockDownloader::new(self, heights, workers)
     }
 }
 
@@ -130,9 +361,91 @@ impl Client {
     /// # Returns
     ///
     /// The deserialized `Block` struct.
+    ///
+    /// The block is fetched with [`Client::get_block_bytes`], over the REST interface if enabled,
+    /// and fails with `Error::BlockPruned` if the node pruned it.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::Decode` if the block is malformed. Earlier versions returned
+    ///   `Error::DecodeHex` instead, see the changelog.
     pub fn get_block(&self, block_hash: &BlockHash) -> Result<Block, Error> {
-        self.call::<String>("getblock", &[json!(block_hash), json!(0)])
-            .and_then(|block_hex| deserialize_hex(&block_hex).map_err(Error::DecodeHex))
+        let bytes = self.get_block_bytes(block_hash)?;
+        deserialize(&bytes).map_err(Error::Decode)
+    }
+
+    /// Retrieves the consensus-encoded bytes of the block with the given hash.
+    ///
+    /// If the client was built with [`ClientBuilder::rest`], the block is transferred in binary
+    /// from the REST interface, falling back to the JSON-RPC `getblock` if the REST request fails
+    /// with `404 Not Found` or `403 Forbidden`. If the block is then returned over JSON-RPC, the
+    /// node runs without `-rest=1` and the REST interface is no longer tried. Otherwise, the hex
+    /// returned by `getblock` is decoded straight from the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The serialized block, which can be deserialized lazily, e.g. with
+    /// [`consensus::deserialize`](corepc_types::bitcoin::consensus::deserialize).
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::Rest` if the REST request fails with another error.
+    /// * Returns `Error::BlockPruned` if the node pruned the block, and it was not downloaded again
+    ///   in time if the client was built with [`ClientBuilder::fetch_pruned_blocks`].
+    pub fn get_block_bytes(&self, block_hash: &BlockHash) -> Result<Vec<u8>, Error> {
+        let mut rest_failed = false;
+        if let Some(rest) = &self.rest {
+            if !self.rest_unavailable.load(Ordering::Relaxed) {
+                match rest.get_block_bytes(block_hash) {
+                    Ok(bytes) => return Ok(bytes),
+                    Err(e) if rest::should_fall_back(&e) => rest_failed = true,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        let result = self.get_block_bytes_rpc(block_hash);
+        if rest_failed && result.is_ok() {
+            self.rest_unavailable.store(true, Ordering::Relaxed);
+        }
+        match (&result, self.pruned_block_timeout()) {
+            (Err(Error::BlockPruned(_)), Some(timeout)) => {
+                self.fetch_pruned_block(block_hash, timeout)
+            }
+            _ => result,
+        }
+    }
+
+    /// Retrieves the consensus-encoded bytes of a block with `getblock`.
+    fn get_block_bytes_rpc(&self, block_hash: &BlockHash) -> Result<Vec<u8>, Error> {
+        self.call::<HexBytes>("getblock", &[json!(block_hash), json!(0)])
+            .map(|bytes| bytes.0)
+            .map_err(|e| prune::classify_block_error(block_hash, e))
+    }
+
+    /// Requests a pruned block from the peers of the node, and waits up to `timeout` for it to
+    /// arrive.
+    fn fetch_pruned_block(
+        &self,
+        block_hash: &BlockHash,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Error> {
+        let deadline = Instant::now() + timeout;
+        let peers = self.call::<Vec<PeerId>>("getpeerinfo", &[])?;
+        let requested = peers
+            .iter()
+            .any(|peer| self.get_block_from_peer(block_hash, peer.id).is_ok());
+        loop {
+            match self.get_block_bytes_rpc(block_hash) {
+                Err(Error::BlockPruned(_)) if requested && Instant::now() < deadline => {
+                    std::thread::sleep(prune::POLL_INTERVAL)
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Retrieves the hash of the best chain's block.
@@ -223,13 +536,35 @@ impl Client {
         self.call::<String>("getrawtransaction", &[json!(txid)])
             .and_then(|tx_hex| deserialize_hex(&tx_hex).map_err(Error::DecodeHex))
     }
-}
 
-#[cfg(not(feature = "28_0"))]
-use corepc_types::model::{GetBlockHeaderVerbose, GetBlockVerboseOne};
+    /// Retrieves a transaction with the block it is confirmed in, if any (verbosity 1).
+    ///
+    /// Without `-txindex`, only mempool transactions are found, unless the block containing the
+    /// transaction is given. The response schema is the same on all supported Bitcoin Core
+    /// versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `txid`: The transaction ID to retrieve.
+    /// * `block_hash`: The hash of the block to look for the transaction in, if known.
+    ///
+    /// # Returns
+    ///
+    /// The transaction, and the hash, time and number of confirmations of its block, which are
+    /// `None` for mempool transactions, as a `GetRawTransactionVerbose` struct.
+    /// `in_active_chain` is only set if `block_hash` is given, and is `false` if that block is not
+    /// in the best chain, e.g. because it was reorganized out.
+    pub fn get_raw_transaction_info(
+        &self,
+        txid: &Txid,
+        block_hash: Option<&BlockHash>,
+    ) -> Result<GetRawTransactionVerbose, Error> {
+        let args = [json!(txid), json!(1), json!(block_hash)];
+        self.call::<v30::GetRawTransactionVerbose>("getrawtransaction", &args)?
+            .into_model()
+            .map_err(Error::GetRawTransactionVerbose)
+    }
 
-#[cfg(not(feature = "28_0"))]
-impl Client {
     /// Retrieves the verbose JSON representation of a block header (verbosity 1).
     ///
     /// # Arguments
@@ -243,11 +578,17 @@ impl Client {
         &self,
         block_hash: &BlockHash,
     ) -> Result<GetBlockHeaderVerbose, Error> {
-        let header_info: v30::GetBlockHeaderVerbose =
-            self.call("getblockheader", &[json!(block_hash)])?;
-        header_info
-            .into_model()
-            .map_err(Error::GetBlockHeaderVerbose)
+        let args = [json!(block_hash)];
+        match self.server_version()? {
+            CoreVersion::V28 => self
+                .call::<v28::GetBlockHeaderVerbose>("getblockheader", &args)?
+                .into_model()
+                .map_err(Error::GetBlockHeaderVerboseV28),
+            _ => self
+                .call::<v30::GetBlockHeaderVerbose>("getblockheader", &args)?
+                .into_model()
+                .map_err(Error::GetBlockHeaderVerbose),
+        }
     }
 
     /// Retrieves the verbose JSON representation of a block (verbosity 1).
@@ -260,10 +601,457 @@ impl Client {
     ///
     /// The verbose block data as a `GetBlockVerboseOne` struct.
     pub fn get_block_verbose(&self, block_hash: &BlockHash) -> Result<GetBlockVerboseOne, Error> {
-        let block_info: v30::GetBlockVerboseOne =
-            self.call("getblock", &[json!(block_hash), json!(1)])?;
-        block_info.into_model().map_err(Error::GetBlockVerboseOne)
+        let args = [json!(block_hash), json!(1)];
+        match self.server_version()? {
+            CoreVersion::V28 => self
+                .call::<v28::GetBlockVerboseOne>("getblock", &args)?
+                .into_model()
+                .map_err(Error::GetBlockVerboseOneV28),
+            _ => self
+                .call::<v30::GetBlockVerboseOne>("getblock", &args)?
+                .into_model()
+                .map_err(Error::GetBlockVerboseOne),
+        }
+    }
+
+    /// Retrieves the state of the best chain, such as the network, the tip, the verification
+    /// progress and whether the node is in initial block download or pruned.
+    ///
+    /// # Returns
+    ///
+    /// The chain state as a `GetBlockchainInfo` struct. `bits`, `target` and `signet_challenge`
+    /// are `None`, and `softforks` is populated, on Bitcoin Core v28 only.
+    pub fn get_blockchain_info(&self) -> Result<GetBlockchainInfo, Error> {
+        match self.server_version()? {
+            CoreVersion::V28 => self
+                .call::<v28::GetBlockchainInfo>("getblockchaininfo", &[])?
+                .into_model()
+                .map_err(Error::GetBlockchainInfoV28),
+            _ => self
+                .call::<v30::GetBlockchainInfo>("getblockchaininfo", &[])?
+                .into_model()
+                .map_err(Error::GetBlockchainInfo),
+        }
+    }
+
+    /// Retrieves the tips of all the chains known to the node, including the best chain and
+    /// orphaned branches.
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions.
+    ///
+    /// # Returns
+    ///
+    /// The height, hash, branch length and status of each tip, as a `GetChainTips` struct.
+    pub fn get_chain_tips(&self) -> Result<GetChainTips, Error> {
+        self.call::<v30::GetChainTips>("getchaintips", &[])?
+            .into_model()
+            .map_err(Error::GetChainTips)
+    }
+
+    /// Retrieves statistics about the total number and rate of transactions in the chain.
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `nblocks`: The size of the window, in blocks. Defaults to one month of blocks.
+    /// * `block_hash`: The hash of the block ending the window. Defaults to the tip.
+    ///
+    /// # Returns
+    ///
+    /// The transaction count and rate over the window, as a `GetChainTxStats` struct.
+    pub fn get_chain_tx_stats(
+        &self,
+        nblocks: Option<u32>,
+        block_hash: Option<&BlockHash>,
+    ) -> Result<GetChainTxStats, Error> {
+        let args = [json!(nblocks), json!(block_hash)];
+        self.call::<v30::GetChainTxStats>("getchaintxstats", &args)?
+            .into_model()
+            .map_err(Error::GetChainTxStats)
+    }
+
+    /// Retrieves the height of the lowest block whose data is stored by the node.
+    ///
+    /// # Returns
+    ///
+    /// The prune height, or `None` if the node is not pruned.
+    pub fn prune_height(&self) -> Result<Option<u32>, Error> {
+        let info = self.get_blockchain_info()?;
+        Ok(info.prune_height.filter(|_| info.pruned))
+    }
+
+    /// Requests the block with the given hash from a peer, e.g. to fetch a pruned block again.
+    ///
+    /// The request is asynchronous: the block is available once the peer sent it, see
+    /// [`ClientBuilder::fetch_pruned_blocks`] to wait for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block, whose header must be known to the node.
+    /// * `peer_id`: The index of the peer, as returned by `getpeerinfo`.
+    pub fn get_block_from_peer(&self, block_hash: &BlockHash, peer_id: u64) -> Result<(), Error> {
+        self.call::<serde_json::Value>("getblockfrompeer", &[json!(block_hash), json!(peer_id)])
+            .map(|_| ())
+    }
+
+    /// Creates and loads a new descriptor wallet.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the new wallet.
+    ///
+    /// # Returns
+    ///
+    /// The name of the created wallet and any warnings, as a `CreateWallet` struct.
+    pub fn create_wallet(&self, name: &str) -> Result<CreateWallet, Error> {
+        self.call::<v30::CreateWallet>("createwallet", &[json!(name)])
+            .map(|wallet| wallet.into_model())
     }
+
+    /// Loads the wallet `name` from the wallet directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the wallet to load.
+    ///
+    /// # Returns
+    ///
+    /// The name of the loaded wallet and any warnings, as a `LoadWallet` struct.
+    pub fn load_wallet(&self, name: &str) -> Result<LoadWallet, Error> {
+        self.call::<v30::LoadWallet>("loadwallet", &[json!(name)])
+            .map(|wallet| wallet.into_model())
+    }
+
+    /// Unloads the wallet `name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the wallet to unload. If this client targets a wallet, it must be the
+    ///   same wallet.
+    ///
+    /// # Returns
+    ///
+    /// Any warnings, as an `UnloadWallet` struct.
+    pub fn unload_wallet(&self, name: &str) -> Result<UnloadWallet, Error> {
+        self.call::<v30::UnloadWallet>("unloadwallet", &[json!(name)])
+            .map(|wallet| wallet.into_model())
+    }
+
+    /// Retrieves the names of the loaded wallets.
+    ///
+    /// # Returns
+    ///
+    /// A vector of wallet names.
+    pub fn list_wallets(&self) -> Result<Vec<String>, Error> {
+        self.call::<ListWallets>("listwallets", &[])
+            .map(|wallets| wallets.0)
+    }
+
+    /// Waits for a new block to be connected to the best chain.
+    ///
+    /// The request timeout is extended by `timeout`, so the long poll is not aborted by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout`: How long to wait, with millisecond granularity. `bitcoind` waits indefinitely
+    ///   if zero, but the request is still aborted after the client's request timeout.
+    ///
+    /// # Returns
+    ///
+    /// The hash and height of the new tip, or of the current tip if the timeout expired first.
+    pub fn wait_for_new_block(&self, timeout: Duration) -> Result<(BlockHash, u32), Error> {
+        let args = [json!(timeout_millis(timeout))];
+        let block = self
+            .call_with_timeout::<v30::WaitForNewBlock>("waitfornewblock", &args, timeout)?
+            .into_model()
+            .map_err(Error::WaitForNewBlock)?;
+        Ok((block.hash, block.height))
+    }
+
+    /// Waits for the block with the given hash to be connected to the best chain.
+    ///
+    /// The request timeout is extended by `timeout`, so the long poll is not aborted by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to wait for.
+    /// * `timeout`: How long to wait, with millisecond granularity. `bitcoind` waits indefinitely
+    ///   if zero, but the request is still aborted after the client's request timeout.
+    ///
+    /// # Returns
+    ///
+    /// The hash and height of the tip, which is not `block_hash` if the timeout expired first.
+    pub fn wait_for_block(
+        &self,
+        block_hash: &BlockHash,
+        timeout: Duration,
+    ) -> Result<(BlockHash, u32), Error> {
+        let args = [json!(block_hash), json!(timeout_millis(timeout))];
+        let block = self
+            .call_with_timeout::<v30::WaitForBlock>("waitforblock", &args, timeout)?
+            .into_model()
+            .map_err(Error::WaitForBlock)?;
+        Ok((block.hash, block.height))
+    }
+
+    /// Waits for the best chain to reach `height`.
+    ///
+    /// The request timeout is extended by `timeout`, so the long poll is not aborted by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `height`: The height to wait for.
+    /// * `timeout`: How long to wait, with millisecond granularity. `bitcoind` waits indefinitely
+    ///   if zero, but the request is still aborted after the client's request timeout.
+    ///
+    /// # Returns
+    ///
+    /// The hash and height of the tip, which is below `height` if the timeout expired first.
+    pub fn wait_for_block_height(
+        &self,
+        height: u32,
+        timeout: Duration,
+    ) -> Result<(BlockHash, u32), Error> {
+        let args = [json!(height), json!(timeout_millis(timeout))];
+        let block = self
+            .call_with_timeout::<v30::WaitForBlockHeight>("waitforblockheight", &args, timeout)?
+            .into_model()
+            .map_err(Error::WaitForBlockHeight)?;
+        Ok((block.hash, block.height))
+    }
+
+    /// Retrieves the ZMQ notifications published by the node, configured with
+    /// `-zmqpub<topic>=<address>`.
+    ///
+    /// # Returns
+    ///
+    /// The type, e.g. `pubhashblock`, address and high water mark of each notification. Empty if
+    /// ZMQ notifications are disabled.
+    pub fn get_zmq_notifications(&self) -> Result<Vec<GetZmqNotifications>, Error> {
+        self.call("getzmqnotifications", &[])
+    }
+
+    /// Submits a raw transaction to the node, which validates it and relays it to its peers.
+    ///
+    /// Uses the default `bitcoind` safety limits, see [`Client::send_raw_transaction_with_options`].
+    ///
+    /// # Arguments
+    ///
+    /// * `tx`: The transaction to broadcast.
+    ///
+    /// # Returns
+    ///
+    /// The `Txid` of the broadcast transaction.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::TxRejected` if the transaction was rejected, e.g. by mempool policy.
+    pub fn send_raw_transaction(&self, tx: &Transaction) -> Result<Txid, Error> {
+        self.send_raw_transaction_with_options(tx, BroadcastOptions::default())
+    }
+
+    /// Submits a raw transaction to the node, rejecting it if it exceeds the limits in `options`.
+    ///
+    /// The broadcast is never retried by the [`RetryPolicy`]: if it times out, the transaction may
+    /// still have been accepted, check with [`Client::get_raw_transaction`] before sending it
+    /// again.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx`: The transaction to broadcast.
+    /// * `options`: The maximum fee rate and burn amount accepted.
+    ///
+    /// # Returns
+    ///
+    /// The `Txid` of the broadcast transaction.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::TxRejected` if the transaction was rejected, e.g. by mempool policy.
+    pub fn send_raw_transaction_with_options(
+        &self,
+        tx: &Transaction,
+        options: BroadcastOptions,
+    ) -> Result<Txid, Error> {
+        let [max_fee_rate, max_burn_amount] = options.to_args();
+        let args = [json!(serialize_hex(tx)), max_fee_rate, max_burn_amount];
+        self.call::<v30::SendRawTransaction>("sendrawtransaction", &args)
+            .map_err(|e| TxRejection::from_error(tx.compute_txid(), e))?
+            .txid()
+            .map_err(Error::HexToArray)
+    }
+
+    /// Checks whether raw transactions would be accepted by the mempool, without submitting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `txs`: The transactions to test. If more than one, they must form a package, where
+    ///   children come after their parents.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `MempoolAcceptance`, one per transaction, holding either its vsize and fees, or
+    /// its reject reason. See [`RejectReason::from_message`](crate::RejectReason::from_message) to
+    /// classify the reject reason.
+    pub fn test_mempool_accept(
+        &self,
+        txs: &[Transaction],
+    ) -> Result<Vec<MempoolAcceptance>, Error> {
+        let raw_txs = txs.iter().map(serialize_hex).collect::<Vec<_>>();
+        self.call::<v30::TestMempoolAccept>("testmempoolaccept", &[json!(raw_txs)])?
+            .into_model()
+            .map(|accept| accept.results)
+            .map_err(Error::TestMempoolAccept)
+    }
+
+    /// Submits a package of raw transactions to the node, which validates them together, e.g. so a
+    /// child can pay for a parent below the mempool minimum fee (CPFP).
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions. Like
+    /// [`Client::send_raw_transaction_with_options`], the package is never retried by the
+    /// [`RetryPolicy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `txs`: The package, made of a child and its parents, sorted so that the child comes last.
+    /// * `options`: The maximum fee rate and burn amount accepted.
+    ///
+    /// # Returns
+    ///
+    /// The `SubmitPackage` result, with the per-transaction results keyed by `Wtxid`, their
+    /// effective fee rates and the txids of the transactions replaced by the package.
+    pub fn submit_package(
+        &self,
+        txs: &[Transaction],
+        options: BroadcastOptions,
+    ) -> Result<SubmitPackage, Error> {
+        let raw_txs = txs.iter().map(serialize_hex).collect::<Vec<_>>();
+        let [max_fee_rate, max_burn_amount] = options.to_args();
+        let args = [json!(raw_txs), max_fee_rate, max_burn_amount];
+        self.call::<v30::SubmitPackage>("submitpackage", &args)?
+            .into_model()
+            .map_err(Error::SubmitPackage)
+    }
+
+    /// Estimates the fee rate needed for a transaction to confirm within `conf_target` blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `conf_target`: The confirmation target in blocks, between 1 and 1008.
+    /// * `mode`: The estimate mode.
+    ///
+    /// # Returns
+    ///
+    /// The estimated `FeeRate`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::FeeEstimateUnavailable` with the errors reported by `bitcoind` if there is
+    ///   no estimate, e.g. because the node has not seen enough transactions confirm yet.
+    pub fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        mode: EstimateMode,
+    ) -> Result<FeeRate, Error> {
+        let args = [json!(conf_target), json!(mode.as_str())];
+        fees::into_fee_rate(self.call("estimatesmartfee", &args)?)
+    }
+
+    /// Estimates the fee rates needed to confirm within each of the `targets`, in a single batch
+    /// request. See [`FEE_TABLE_TARGETS`](crate::FEE_TABLE_TARGETS) for a typical set of targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets`: The confirmation targets in blocks.
+    /// * `mode`: The estimate mode.
+    ///
+    /// # Returns
+    ///
+    /// The estimated `FeeRate` of each target. Targets without an estimate are left out.
+    pub fn estimate_fee_table(
+        &self,
+        targets: &[u16],
+        mode: EstimateMode,
+    ) -> Result<BTreeMap<u16, FeeRate>, Error> {
+        let mut batch = self.batch();
+        let calls = targets
+            .iter()
+            .map(|target| (*target, batch.estimate_smart_fee(*target, mode)))
+            .collect::<Vec<_>>();
+        let responses = batch.send()?;
+
+        let mut table = BTreeMap::new();
+        for (target, call) in calls {
+            match responses.get(&call) {
+                Ok(fee_rate) => {
+                    table.insert(target, fee_rate);
+                }
+                Err(Error::FeeEstimateUnavailable(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+/// Bytes deserialized from a hex string, without an intermediate `String`.
+pub(crate) struct HexBytes(pub(crate) Vec<u8>);
+
+impl<'de> serde::Deserialize<'de> for HexBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct HexVisitor;
+
+        impl serde::de::Visitor<'_> for HexVisitor {
+            type Value = HexBytes;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a hex string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Vec::from_hex(v).map(HexBytes).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
+/// Verifies that a node reporting `chain` and whose genesis block hash is `genesis_hash` is on the
+/// `expected` network.
+pub(crate) fn verify_network(
+    expected: Network,
+    chain: Network,
+    genesis_hash: BlockHash,
+) -> Result<(), Error> {
+    if chain != expected {
+        return Err(Error::NetworkMismatch {
+            expected,
+            actual: Some(chain),
+        });
+    }
+    if genesis_hash != genesis_block(expected).block_hash() {
+        return Err(Error::NetworkMismatch {
+            expected,
+            actual: None,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the `timeout` argument of the `waitfor*` RPCs, in milliseconds.
+///
+/// `bitcoind` parses it as an `int`, so longer timeouts are clamped.
+pub(crate) fn timeout_millis(timeout: Duration) -> i32 {
+    i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX)
 }
 
 #[cfg(test)]
@@ -296,6 +1084,64 @@ mod test_auth {
         std::fs::remove_file(cookie_path).ok();
     }
 
+    #[test]
+    fn test_hex_bytes() {
+        let bytes: HexBytes = serde_json::from_str("\"0001feff\"").unwrap();
+        assert_eq!(bytes.0, vec![0x00, 0x01, 0xfe, 0xff]);
+
+        assert!(serde_json::from_str::<HexBytes>("\"0g\"").is_err());
+        assert!(serde_json::from_str::<HexBytes>("\"012\"").is_err());
+        assert!(serde_json::from_str::<HexBytes>("12").is_err());
+    }
+
+    #[test]
+    fn test_verify_network() {
+        let regtest = genesis_block(Network::Regtest).block_hash();
+        let mainnet = genesis_block(Network::Bitcoin).block_hash();
+
+        assert!(verify_network(Network::Regtest, Network::Regtest, regtest).is_ok());
+        assert!(matches!(
+            verify_network(Network::Bitcoin, Network::Regtest, regtest),
+            Err(Error::NetworkMismatch {
+                expected: Network::Bitcoin,
+                actual: Some(Network::Regtest),
+            })
+        ));
+        assert!(matches!(
+            verify_network(Network::Regtest, Network::Regtest, mainnet),
+            Err(Error::NetworkMismatch {
+                expected: Network::Regtest,
+                actual: None,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_derived_clients_keep_retry_policy() {
+        let auth = Auth::UserPass("user".to_string(), "pass".to_string());
+        let client = Client::with_auth("http://127.0.0.1:18443", auth)
+            .unwrap()
+            .with_retry_policy(RetryPolicy::new().max_attempts(3));
+
+        let wallet = client.wallet("w").unwrap();
+        let clone = client.try_clone().unwrap();
+        for client in [&client, &wallet, &clone] {
+            let policy = client.retry_policy.as_ref().expect("missing retry policy");
+            assert_eq!(policy.get_max_attempts(), 3);
+        }
+    }
+
+    #[test]
+    fn test_timeout_millis() {
+        assert_eq!(timeout_millis(Duration::ZERO), 0);
+        assert_eq!(timeout_millis(Duration::from_millis(1500)), 1500);
+        assert_eq!(
+            timeout_millis(Duration::from_secs(30 * 24 * 3600)),
+            i32::MAX
+        );
+        assert_eq!(timeout_millis(Duration::MAX), i32::MAX);
+    }
+
     #[test]
     fn test_auth_invalid_cookie_file() {
         let dummy_url = "http://127.0.0.1:18443";
@@ -0,0 +1,398 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Discovery of the RPC URL and credentials of a `bitcoind` node from its data directory.
+//!
+//! Mirrors how `bitcoin-cli` reads `bitcoin.conf`: network sections (`[main]`, `[test]`,
+//! `[testnet4]`, `[signet]`, `[regtest]`) and `<network>.<key>` prefixes override top-level
+//! settings, the first value of a repeated setting wins, and `rpcport`/`rpcbind` outside of a
+//! section only apply to mainnet. The node is reached at `rpcconnect`, which may include a port,
+//! but an explicit `rpcport` takes precedence.
+
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use corepc_types::bitcoin::Network;
+
+use crate::client::Auth;
+use crate::error::Error;
+
+/// Settings that Bitcoin Core ignores outside of a network section, unless on mainnet.
+const NETWORK_ONLY: &[&str] = &["rpcport", "rpcbind"];
+
+/// The RPC URL and credentials of a `bitcoind` node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcConfig {
+    /// URL of the RPC server, e.g. `http://127.0.0.1:8332`.
+    pub url: String,
+    /// Credentials to authenticate with.
+    pub auth: Auth,
+}
+
+impl RpcConfig {
+    /// Reads the RPC configuration of a node running on `network` from `bitcoin.conf`.
+    ///
+    /// If `datadir` is `None`, the default data directory of the platform is used:
+    /// `~/.bitcoin` on Linux, `~/Library/Application Support/Bitcoin` on macOS and
+    /// `%APPDATA%\Bitcoin` on Windows. A missing `bitcoin.conf` is treated as an empty one.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if the default data directory can't be located or
+    ///   `bitcoin.conf` contains an invalid RPC setting.
+    /// * Returns `Error::Io` if `bitcoin.conf` exists but can't be read.
+    pub fn from_datadir(network: Network, datadir: Option<&Path>) -> Result<Self, Error> {
+        let datadir = match datadir {
+            Some(datadir) => datadir.to_path_buf(),
+            None => default_datadir()?,
+        };
+        let contents = match fs::read_to_string(datadir.join("bitcoin.conf")) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Self::from_conf(network, &datadir, &contents)
+    }
+
+    /// Reads the RPC configuration of a node running on `network` from the `contents` of the
+    /// `bitcoin.conf` in `datadir`.
+    pub(crate) fn from_conf(
+        network: Network,
+        datadir: &Path,
+        contents: &str,
+    ) -> Result<Self, Error> {
+        let conf = ConfFile::parse(contents);
+        let chain = network.to_core_arg();
+
+        // Like `bitcoin-cli`, an explicit `rpcport` overrides the port of `rpcconnect`.
+        let port_in = |key| {
+            let (_, port) = split_port(conf.get(chain, key)?)?;
+            Some((key, port))
+        };
+        let port = match conf
+            .get(chain, "rpcport")
+            .map(|port| ("rpcport", port))
+            .or_else(|| port_in("rpcconnect"))
+            .or_else(|| port_in("rpcbind"))
+        {
+            Some((key, port)) => port
+                .parse::<u16>()
+                .map_err(|_| Error::InvalidConfig(format!("invalid {key} port: {port}")))?,
+            None => default_rpc_port(chain),
+        };
+        let host = conf
+            .get(chain, "rpcconnect")
+            .or_else(|| conf.get(chain, "rpcbind"))
+            .map(|host| split_port(host).map_or(host, |(host, _)| host))
+            .and_then(connectable_host)
+            .unwrap_or_else(|| "127.0.0.1".to_owned());
+
+        let auth = match conf.get(chain, "rpcpassword") {
+            Some(password) => Auth::UserPass(
+                conf.get(chain, "rpcuser").unwrap_or_default().to_owned(),
+                password.to_owned(),
+            ),
+            None => {
+                let network_dir = network_datadir(datadir, chain);
+                let cookie_file = conf.get(chain, "rpccookiefile").unwrap_or(".cookie");
+                Auth::CookieFile(network_dir.join(cookie_file))
+            }
+        };
+
+        Ok(Self {
+            url: format!("http://{host}:{port}"),
+            auth,
+        })
+    }
+}
+
+/// The settings of a `bitcoin.conf` file.
+#[derive(Debug, Default)]
+struct ConfFile {
+    /// The section (if any), key and value of each setting, in file order.
+    entries: Vec<(Option<String>, String, String)>,
+}
+
+impl ConfFile {
+    /// Parses the contents of a `bitcoin.conf` file, skipping lines it doesn't understand.
+    fn parse(contents: &str) -> Self {
+        let mut entries = Vec::new();
+        let mut section = None;
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.trim().to_owned());
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            // `regtest.rpcport=1234` is the same as `rpcport=1234` in the `[regtest]` section.
+            let (key_section, key) = match key.split_once('.') {
+                Some((prefix, key)) => (Some(prefix.to_owned()), key),
+                None => (section.clone(), key),
+            };
+            entries.push((key_section, key.to_owned(), value.to_owned()));
+        }
+
+        Self { entries }
+    }
+
+    /// Returns the value of `key` on the network `chain`.
+    fn get(&self, chain: &str, key: &str) -> Option<&str> {
+        let find = |section: Option<&str>| {
+            self.entries
+                .iter()
+                .find(|(s, k, _)| s.as_deref() == section && k == key)
+                .map(|(_, _, value)| value.as_str())
+        };
+        let top_level_applies = chain == "main" || !NETWORK_ONLY.contains(&key);
+
+        find(Some(chain)).or_else(|| top_level_applies.then(|| find(None)).flatten())
+    }
+}
+
+/// Returns the default data directory of Bitcoin Core on this platform.
+fn default_datadir() -> Result<PathBuf, Error> {
+    let missing = || Error::InvalidConfig("could not locate the default data directory".into());
+    if cfg!(target_os = "windows") {
+        let appdata = std::env::var_os("APPDATA").ok_or_else(missing)?;
+        Ok(PathBuf::from(appdata).join("Bitcoin"))
+    } else {
+        let home = std::env::var_os("HOME").ok_or_else(missing)?;
+        if cfg!(target_os = "macos") {
+            Ok(PathBuf::from(home).join("Library/Application Support/Bitcoin"))
+        } else {
+            Ok(PathBuf::from(home).join(".bitcoin"))
+        }
+    }
+}
+
+/// Returns the directory holding the files of the network `chain` inside `datadir`.
+fn network_datadir(datadir: &Path, chain: &str) -> PathBuf {
+    match chain {
+        "main" => datadir.to_path_buf(),
+        "test" => datadir.join("testnet3"),
+        chain => datadir.join(chain),
+    }
+}
+
+/// Returns the default RPC port of the network `chain`.
+fn default_rpc_port(chain: &str) -> u16 {
+    match chain {
+        "test" => 18332,
+        "testnet4" => 48332,
+        "signet" => 38332,
+        "regtest" => 18443,
+        _ => 8332,
+    }
+}
+
+/// Splits `host:port`, `[ipv6]:port` or `[ipv6]` into a host and an optional port, returning
+/// `None` if `addr` has no port.
+fn split_port(addr: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = addr.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        return rest.strip_prefix(':').map(|port| (host, port));
+    }
+    // A bare IPv6 address has several colons and no port.
+    match addr.matches(':').count() {
+        1 => addr.split_once(':'),
+        _ => None,
+    }
+}
+
+/// Formats `host` for a URL, returning `None` for wildcard addresses clients can't connect to.
+fn connectable_host(host: &str) -> Option<String> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<IpAddr>() {
+        Ok(ip) if ip.is_unspecified() => None,
+        Ok(IpAddr::V6(ip)) => Some(format!("[{ip}]")),
+        _ => Some(host.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+
+    fn conf(network: Network, contents: &str) -> RpcConfig {
+        RpcConfig::from_conf(network, Path::new("/data"), contents).unwrap()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = conf(Network::Bitcoin, "");
+        assert_eq!(config.url, "http://127.0.0.1:8332");
+        assert_eq!(config.auth, Auth::CookieFile("/data/.cookie".into()));
+
+        let config = conf(Network::Testnet, "");
+        assert_eq!(config.url, "http://127.0.0.1:18332");
+        assert_eq!(
+            config.auth,
+            Auth::CookieFile("/data/testnet3/.cookie".into())
+        );
+
+        let config = conf(Network::Signet, "");
+        assert_eq!(config.url, "http://127.0.0.1:38332");
+        assert_eq!(config.auth, Auth::CookieFile("/data/signet/.cookie".into()));
+
+        let config = conf(Network::Regtest, "");
+        assert_eq!(config.url, "http://127.0.0.1:18443");
+        assert_eq!(
+            config.auth,
+            Auth::CookieFile("/data/regtest/.cookie".into())
+        );
+    }
+
+    #[test]
+    fn test_user_pass() {
+        let contents = "
+            # Credentials shared by all networks
+            rpcuser=alice
+            rpcpassword=secret # trailing comment
+        ";
+        let config = conf(Network::Regtest, contents);
+        assert_eq!(
+            config.auth,
+            Auth::UserPass("alice".to_string(), "secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let contents = "
+            regtest=1
+            rpcport=1111
+            rpcuser=main-user
+            rpcpassword=main-pass
+
+            [regtest]
+            rpcport=2222
+            rpcuser=regtest-user
+            rpcpassword=regtest-pass
+
+            [signet]
+            rpccookiefile=/run/bitcoind/signet.cookie
+        ";
+        let config = conf(Network::Bitcoin, contents);
+        assert_eq!(config.url, "http://127.0.0.1:1111");
+        assert_eq!(
+            config.auth,
+            Auth::UserPass("main-user".to_string(), "main-pass".to_string())
+        );
+
+        let config = conf(Network::Regtest, contents);
+        assert_eq!(config.url, "http://127.0.0.1:2222");
+        assert_eq!(
+            config.auth,
+            Auth::UserPass("regtest-user".to_string(), "regtest-pass".to_string())
+        );
+
+        // The top-level `rpcport` only applies to mainnet, but credentials apply everywhere.
+        let config = conf(Network::Testnet, contents);
+        assert_eq!(config.url, "http://127.0.0.1:18332");
+        assert_eq!(
+            config.auth,
+            Auth::UserPass("main-user".to_string(), "main-pass".to_string())
+        );
+    }
+
+    #[test]
+    fn test_network_prefix_and_cookie_file() {
+        let contents = "
+            signet.rpcport=3333
+            signet.rpccookiefile=/run/bitcoind/signet.cookie
+            regtest.rpccookiefile=custom.cookie
+        ";
+        let config = conf(Network::Signet, contents);
+        assert_eq!(config.url, "http://127.0.0.1:3333");
+        assert_eq!(
+            config.auth,
+            Auth::CookieFile("/run/bitcoind/signet.cookie".into())
+        );
+
+        let config = conf(Network::Regtest, contents);
+        assert_eq!(
+            config.auth,
+            Auth::CookieFile("/data/regtest/custom.cookie".into())
+        );
+    }
+
+    #[test]
+    fn test_rpcbind_and_rpcconnect() {
+        let config = conf(Network::Bitcoin, "rpcbind=10.0.0.2:9999");
+        assert_eq!(config.url, "http://10.0.0.2:9999");
+
+        let config = conf(Network::Bitcoin, "rpcbind=0.0.0.0\nrpcbind=10.0.0.2");
+        assert_eq!(config.url, "http://127.0.0.1:8332");
+
+        let config = conf(Network::Bitcoin, "rpcbind=[::1]:9999");
+        assert_eq!(config.url, "http://[::1]:9999");
+
+        let config = conf(Network::Bitcoin, "rpcbind=::1");
+        assert_eq!(config.url, "http://[::1]:8332");
+
+        let config = conf(
+            Network::Regtest,
+            "[regtest]\nrpcbind=0.0.0.0:2222\nrpcconnect=node.local",
+        );
+        assert_eq!(config.url, "http://node.local:2222");
+
+        // The first value of a repeated setting wins.
+        let config = conf(Network::Bitcoin, "rpcport=1111\nrpcport=2222");
+        assert_eq!(config.url, "http://127.0.0.1:1111");
+
+        let config = conf(Network::Bitcoin, "rpcconnect=10.0.0.3:7777");
+        assert_eq!(config.url, "http://10.0.0.3:7777");
+
+        let config = conf(
+            Network::Bitcoin,
+            "rpcconnect=[::1]:7777\nrpcbind=0.0.0.0:2222",
+        );
+        assert_eq!(config.url, "http://[::1]:7777");
+
+        let config = conf(Network::Bitcoin, "rpcconnect=[::1]");
+        assert_eq!(config.url, "http://[::1]:8332");
+
+        // An explicit `rpcport` takes precedence over the port of `rpcconnect`.
+        let config = conf(
+            Network::Regtest,
+            "[regtest]\nrpcconnect=node.local:7777\nrpcport=1234",
+        );
+        assert_eq!(config.url, "http://node.local:1234");
+    }
+
+    #[test]
+    fn test_invalid_port() {
+        let result = RpcConfig::from_conf(Network::Bitcoin, Path::new("/data"), "rpcport=abc");
+        assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+        let contents = "rpcconnect=node.local:abc";
+        let result = RpcConfig::from_conf(Network::Bitcoin, Path::new("/data"), contents);
+        assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_from_datadir() {
+        let datadir = std::env::temp_dir().join(format!("bdk-datadir-{}", std::process::id()));
+        fs::create_dir_all(&datadir).unwrap();
+
+        // A missing `bitcoin.conf` is the same as an empty one.
+        let config = RpcConfig::from_datadir(Network::Regtest, Some(&datadir)).unwrap();
+        assert_eq!(config.url, "http://127.0.0.1:18443");
+        assert_eq!(
+            config.auth,
+            Auth::CookieFile(datadir.join("regtest/.cookie"))
+        );
+
+        fs::write(datadir.join("bitcoin.conf"), "[regtest]\nrpcport=4444\n").unwrap();
+        let config = RpcConfig::from_datadir(Network::Regtest, Some(&datadir)).unwrap();
+        assert_eq!(config.url, "http://127.0.0.1:4444");
+
+        fs::remove_dir_all(&datadir).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Parallel download of a range of blocks.
+//!
+//! The [`BlockDownloader`] spreads the `getblockhash` and `getblock` calls for a height range
+//! over a pool of worker threads, each with its own connection to the node, and yields the blocks
+//! in height order. At most a bounded number of blocks are requested ahead of the one the caller
+//! is waiting for, so memory use doesn't depend on the length of the range.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use corepc_types::bitcoin::Block;
+
+use crate::{Client, Error};
+
+/// Number of blocks requested ahead of the next one to yield, per worker.
+const BLOCKS_AHEAD_PER_WORKER: usize = 4;
+
+/// The outcome of downloading the block at a height.
+type Download = (u32, Result<Block, Error>);
+
+/// An iterator over the blocks of a height range, downloaded in parallel.
+///
+/// Created by [`Client::fetch_blocks`]. Yields `(height, block)` pairs strictly in height order,
+/// and stops after the first error.
+///
+/// The block hash of each height is looked up when the block is downloaded, so if the node
+/// reorganizes during the download, consecutive blocks may belong to different chains. Callers
+/// that need a consistent chain should check each block's `prev_blockhash`.
+///
+/// Dropping the downloader stops the workers, waiting for their in-flight requests to complete.
+#[derive(Debug)]
+pub struct BlockDownloader {
+    /// Height of the next block to yield, `None` once the range is exhausted or after an error.
+    next_height: Option<u32>,
+    /// Height of the next block to request, `None` once all blocks have been requested.
+    next_request: Option<u32>,
+    /// Height of the last block of the range.
+    end_height: u32,
+    /// Maximum number of blocks requested but not yet yielded.
+    max_ahead: usize,
+    /// Downloaded blocks waiting for the blocks before them.
+    downloaded: BTreeMap<u32, Result<Block, Error>>,
+    /// Sends the heights to download to the workers, `None` once the workers are stopped.
+    requests: Option<Sender<u32>>,
+    /// Receives the downloaded blocks from the workers.
+    responses: Receiver<Download>,
+    /// Tells the workers to skip the heights that are still queued.
+    stop: Arc<AtomicBool>,
+    /// The worker threads.
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl BlockDownloader {
+    /// Starts `workers` threads downloading the blocks at `heights` with connections configured
+    /// like `client`.
+    pub(crate) fn new(
+        client: &Client,
+        heights: RangeInclusive<u32>,
+        workers: usize,
+    ) -> Result<Self, Error> {
+        let workers = workers.max(1);
+        let (request_tx, request_rx) = mpsc::channel::<u32>();
+        let (response_tx, response_rx) = mpsc::channel();
+        let request_rx = Arc::new(Mutex::new(request_rx));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let mut downloader = Self {
+            next_height: (!heights.is_empty()).then_some(*heights.start()),
+            next_request: (!heights.is_empty()).then_some(*heights.start()),
+            end_height: *heights.end(),
+            max_ahead: workers * BLOCKS_AHEAD_PER_WORKER,
+            downloaded: BTreeMap::new(),
+            requests: Some(request_tx),
+            responses: response_rx,
+            stop: Arc::clone(&stop),
+            workers: Vec::with_capacity(workers),
+        };
+        if heights.is_empty() {
+            return Ok(downloader);
+        }
+
+        for _ in 0..workers {
+            // On error, dropping `downloader` stops the workers that were already started.
+            let client = client.try_clone()?;
+            let request_rx = Arc::clone(&request_rx);
+            let response_tx = response_tx.clone();
+            let stop = Arc::clone(&stop);
+            downloader.workers.push(thread::spawn(move || {
+                loop {
+                    // The lock is released at the end of the statement, before the download.
+                    let request = request_rx.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    let Ok(height) = request else {
+                        return;
+                    };
+                    if stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    // A panic would leave `next` waiting for this height forever, report it as
+                    // the outcome of the download instead.
+                    let block = panic::catch_unwind(AssertUnwindSafe(|| {
+                        client
+                            .get_block_hash(height)
+                            .and_then(|hash| client.get_block(&hash))
+                    }))
+                    .unwrap_or_else(|_| {
+                        let e = io::Error::other("block download worker panicked");
+                        Err(Error::Io(e))
+                    });
+                    if response_tx.send((height, block)).is_err() {
+                        return;
+                    }
+                }
+            }));
+        }
+
+        Ok(downloader)
+    }
+
+    /// Returns the height of the next block to yield, or `None` if the downloader is done.
+    pub fn next_height(&self) -> Option<u32> {
+        self.next_height
+    }
+
+    /// Queues requests until `max_ahead` blocks are requested but not yet yielded.
+    fn request_blocks(&mut self, next_height: u32) {
+        let Some(requests) = &self.requests else {
+            return;
+        };
+        while let Some(height) = self.next_request {
+            if (height - next_height) as usize >= self.max_ahead {
+                break;
+            }
+            if requests.send(height).is_err() {
+                break;
+            }
+            self.next_request = height
+                .checked_add(1)
+                .filter(|height| *height <= self.end_height);
+        }
+    }
+
+    /// Stops the workers, letting them finish their in-flight requests.
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.requests = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Iterator for BlockDownloader {
+    type Item = Result<(u32, Block), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let height = self.next_height?;
+        self.request_blocks(height);
+
+        let result = loop {
+            if let Some(result) = self.downloaded.remove(&height) {
+                break result;
+            }
+            match self.responses.recv() {
+                Ok((height, result)) => {
+                    self.downloaded.insert(height, result);
+                }
+                // The workers don't exit on their own while blocks are requested.
+                Err(_) => {
+                    let e = io::Error::other("block download workers exited");
+                    break Err(Error::Io(e));
+                }
+            }
+        };
+
+        match result {
+            Ok(block) => {
+                self.next_height = height
+                    .checked_add(1)
+                    .filter(|height| *height <= self.end_height);
+                if self.next_height.is_none() {
+                    self.stop();
+                }
+                Some(Ok((height, block)))
+            }
+            Err(e) => {
+                self.next_height = None;
+                self.downloaded.clear();
+                self.stop();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl Drop for BlockDownloader {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
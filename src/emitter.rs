@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Block emitter that follows the best chain of a `bitcoind` node.
+//!
+//! The [`Emitter`] starts from a known checkpoint and emits every block connected on top of it,
+//! in height order. When the node reorganizes away from a previously emitted block, the emitter
+//! walks back to the fork point, emitting one [`BlockEvent::Disconnected`] per stale block before
+//! resuming on the new best chain.
+
+use corepc_types::bitcoin::{Block, BlockHash};
+
+use crate::{Client, Error};
+
+/// An event emitted by the [`Emitter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockEvent {
+    /// A block was connected on top of the previously emitted tip.
+    Connected {
+        /// Height of the connected block.
+        height: u32,
+        /// The connected block.
+        block: Block,
+    },
+    /// A previously emitted block is no longer part of the node's best chain.
+    Disconnected {
+        /// Height of the disconnected block.
+        height: u32,
+        /// Hash of the disconnected block.
+        hash: BlockHash,
+    },
+}
+
+/// Emits blocks of the best chain of a `bitcoind` node, starting from a checkpoint.
+#[derive(Debug)]
+pub struct Emitter<'a> {
+    /// The client used to query the node.
+    client: &'a Client,
+    /// Height and hash of the last emitted block that is still assumed to be in the best chain.
+    tip: (u32, BlockHash),
+}
+
+impl<'a> Emitter<'a> {
+    /// Creates a new [`Emitter`] that starts emitting blocks after the checkpoint at `height`
+    /// with `hash`.
+    ///
+    /// The checkpoint itself is not emitted.
+    pub fn new(client: &'a Client, height: u32, hash: BlockHash) -> Self {
+        Self {
+            client,
+            tip: (height, hash),
+        }
+    }
+
+    /// Returns the height and hash of the last emitted block.
+    ///
+    /// After a [`BlockEvent::Disconnected`] this is the parent of the disconnected block.
+    pub fn tip(&self) -> (u32, BlockHash) {
+        self.tip
+    }
+
+    /// Returns the next [`BlockEvent`], or `None` if the emitter has caught up with the node's
+    /// best chain.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::CheckpointMismatch` if the checkpoint the emitter was created with is not
+    ///   at the given height.
+    /// * Returns an error if any of the underlying RPC calls fail, including when the node does
+    ///   not know the checkpoint the emitter was created with.
+    pub fn next_block(&mut self) -> Result<Option<BlockEvent>, Error> {
+        let (height, hash) = self.tip;
+        let header = self.client.get_block_header_verbose(&hash)?;
+        if header.height != height {
+            return Err(Error::CheckpointMismatch {
+                hash,
+                height,
+                actual: header.height,
+            });
+        }
+        let height = header.height;
+
+        // A block with negative confirmations is not in the best chain anymore.
+        if header.confirmations < 1 {
+            let Some(prev_hash) = header.previous_block_hash else {
+                // Only the genesis block has no parent, and it can't be reorged out.
+                return Ok(None);
+            };
+            self.tip = (height - 1, prev_hash);
+            return Ok(Some(BlockEvent::Disconnected { height, hash }));
+        }
+
+        let Some(next_hash) = header.next_block_hash else {
+            return Ok(None);
+        };
+
+        let block = self.client.get_block(&next_hash)?;
+        self.tip = (height + 1, next_hash);
+
+        Ok(Some(BlockEvent::Connected {
+            height: height + 1,
+            block,
+        }))
+    }
+}
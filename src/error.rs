@@ -6,13 +6,22 @@ use core::fmt;
 use core::num::TryFromIntError;
 use std::io;
 
-use bitcoin::{consensus::encode::FromHexError, hex::HexToArrayError};
-#[cfg(feature = "28_0")]
-use corepc_types::v17::{GetBlockHeaderVerboseError, GetBlockVerboseOneError};
-#[cfg(not(feature = "28_0"))]
-use corepc_types::v30::{GetBlockHeaderVerboseError, GetBlockVerboseOneError};
-use corepc_types::{bitcoin, v30::GetBlockFilterError};
-use jsonrpc::serde_json;
+use bitcoin::{
+    amount::ParseAmountError,
+    bip158,
+    consensus::encode::{self, FromHexError},
+    hex::HexToArrayError,
+};
+use corepc_types::v30::{
+    ChainTipsError, GetBlockHeaderVerboseError, GetBlockVerboseOneError, GetBlockchainInfoError,
+    GetChainTxStatsError, GetRawTransactionVerboseError, MapMempoolEntryError, SubmitPackageError,
+    TestMempoolAcceptError, WaitForBlockError, WaitForBlockHeightError, WaitForNewBlockError,
+};
+use corepc_types::{bitcoin, v28, v30::GetBlockFilterError};
+use jsonrpc::{bitreq_http, serde_json};
+
+use crate::CoreVersion;
+use crate::broadcast::TxRejection;
 
 /// Result type alias for the RPC client.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -20,54 +29,227 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// Errors that can occur when using the Bitcoin RPC client.
 #[derive(Debug)]
 pub enum Error {
+    /// BIP-0158 compact block filter error.
+    Bip158(bip158::Error),
+
+    /// The data of the block with the given hash was deleted by a pruned node.
+    BlockPruned(bitcoin::BlockHash),
+
+    /// The height of an [`Emitter`](crate::Emitter) checkpoint is not the height of its block.
+    CheckpointMismatch {
+        /// Hash of the checkpoint block.
+        hash: bitcoin::BlockHash,
+        /// The height the checkpoint was created with.
+        height: u32,
+        /// The height of the block reported by `bitcoind`.
+        actual: u32,
+    },
+
+    /// Consensus deserialization error.
+    Decode(encode::Error),
+
     /// Hex deserialization error
     DecodeHex(FromHexError),
 
+    /// Error modeling [`EstimateSmartFee`](corepc_types::model::EstimateSmartFee).
+    EstimateSmartFee(ParseAmountError),
+
+    /// `bitcoind` has no fee estimate for the target, with the errors it reported.
+    FeeEstimateUnavailable(Vec<String>),
+
+    /// A block filter does not hash into the filter header chain, see
+    /// [`FilterHeaderChain`](crate::FilterHeaderChain).
+    FilterHeaderMismatch {
+        /// Height of the block whose filter failed verification.
+        height: u32,
+        /// The header computed from the filter and the previous header.
+        expected: bip158::FilterHeader,
+        /// The header returned by `bitcoind`.
+        actual: bip158::FilterHeader,
+    },
+
     /// Error converting `GetBlockVersboseOne` type into the model type
     GetBlockVerboseOne(GetBlockVerboseOneError),
 
+    /// Error converting the Bitcoin Core v28 `GetBlockVerboseOne` type into the model type
+    GetBlockVerboseOneV28(v28::GetBlockVerboseOneError),
+
     /// Error modeling [`GetBlockHeaderVerbose`](corepc_types::model::GetBlockHeaderVerbose).
     GetBlockHeaderVerbose(GetBlockHeaderVerboseError),
 
+    /// Error modeling [`GetBlockHeaderVerbose`](corepc_types::model::GetBlockHeaderVerbose)
+    /// from a Bitcoin Core v28 response.
+    GetBlockHeaderVerboseV28(v28::GetBlockHeaderVerboseError),
+
     /// Error modeling [`GetBlockFilter`](corepc_types::model::GetBlockFilter)
     GetBlockFilter(GetBlockFilterError),
 
+    /// Error modeling [`GetBlockchainInfo`](corepc_types::model::GetBlockchainInfo).
+    GetBlockchainInfo(GetBlockchainInfoError),
+
+    /// Error modeling [`GetBlockchainInfo`](corepc_types::model::GetBlockchainInfo) from a
+    /// Bitcoin Core v28 response.
+    GetBlockchainInfoV28(v28::GetBlockchainInfoError),
+
+    /// Error modeling [`GetChainTips`](corepc_types::model::GetChainTips).
+    GetChainTips(ChainTipsError),
+
+    /// Error modeling [`GetChainTxStats`](corepc_types::model::GetChainTxStats).
+    GetChainTxStats(GetChainTxStatsError),
+
+    /// Error modeling [`GetRawMempoolVerbose`](corepc_types::model::GetRawMempoolVerbose).
+    GetRawMempoolVerbose(MapMempoolEntryError),
+
+    /// Error modeling [`GetRawTransactionVerbose`](corepc_types::model::GetRawTransactionVerbose).
+    GetRawTransactionVerbose(GetRawTransactionVerboseError),
+
+    /// The operation is not supported by clients created with a custom transport.
+    CustomTransport,
+
+    /// Invalid or missing `bitcoind` configuration.
+    InvalidConfig(String),
+
     /// Invalid or corrupted cookie file.
     InvalidCookieFile,
 
     /// The provided URL is syntactically incorrect
     InvalidUrl(String),
 
+    /// Malformed ZMQ notification.
+    #[cfg(feature = "zmq")]
+    InvalidZmqMessage(String),
+
     /// JSON-RPC error from the server.
     JsonRpc(jsonrpc::Error),
 
+    /// The server did not return a response for a call in a batch request.
+    MissingBatchResponse,
+
+    /// The node is not on the expected network.
+    NetworkMismatch {
+        /// The network the client expects.
+        expected: bitcoin::Network,
+        /// The network reported by the node, or `None` if the node reports the expected network
+        /// but its genesis block is a different one.
+        actual: Option<bitcoin::Network>,
+    },
+
     /// Hash parsing error.
     HexToArray(HexToArrayError),
 
     /// JSON serialization/deserialization error.
     Json(serde_json::Error),
 
+    /// HTTP error from the REST interface.
+    Rest(bitreq_http::Error),
+
     /// I/O error (e.g., reading cookie file, network issues).
     Io(io::Error),
 
+    /// Error modeling [`SubmitPackage`](corepc_types::model::SubmitPackage).
+    SubmitPackage(SubmitPackageError),
+
+    /// Error modeling [`TestMempoolAccept`](corepc_types::model::TestMempoolAccept).
+    TestMempoolAccept(TestMempoolAcceptError),
+
+    /// `bitcoind` refused to accept a transaction.
+    TxRejected(TxRejection),
+
     /// Error when converting an integer type to a smaller type due to overflow.
     TryFromInt(TryFromIntError),
+
+    /// The server runs a Bitcoin Core version older than any supported one.
+    UnsupportedVersion(usize),
+
+    /// Error modeling [`WaitForBlock`](corepc_types::model::WaitForBlock).
+    WaitForBlock(WaitForBlockError),
+
+    /// Error modeling [`WaitForBlockHeight`](corepc_types::model::WaitForBlockHeight).
+    WaitForBlockHeight(WaitForBlockHeightError),
+
+    /// Error modeling [`WaitForNewBlock`](corepc_types::model::WaitForNewBlock).
+    WaitForNewBlock(WaitForNewBlockError),
+
+    /// ZMQ error.
+    #[cfg(feature = "zmq")]
+    Zmq(zmq::Error),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Error::Bip158(e) => write!(f, "block filter error: {e}"),
+            Error::BlockPruned(hash) => write!(f, "block {hash} was pruned"),
+            Error::CheckpointMismatch {
+                hash,
+                height,
+                actual,
+            } => write!(
+                f,
+                "checkpoint {hash} is at height {actual}, not at height {height}"
+            ),
+            Error::Decode(e) => write!(f, "deserialization error: {e}"),
             Error::DecodeHex(e) => write!(f, "hex deserialization error: {e}"),
+            Error::EstimateSmartFee(e) => write!(f, "fee estimate error: {e}"),
+            Error::FeeEstimateUnavailable(errors) if errors.is_empty() => {
+                write!(f, "no fee estimate available")
+            }
+            Error::FeeEstimateUnavailable(errors) => {
+                write!(f, "no fee estimate available: {}", errors.join(", "))
+            }
+            Error::FilterHeaderMismatch {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "filter header mismatch at height {height}: expected {expected}, got {actual}"
+            ),
             Error::GetBlockVerboseOne(e) => write!(f, "block verbose error: {e}"),
+            Error::GetBlockVerboseOneV28(e) => write!(f, "block verbose error: {e}"),
             Error::GetBlockHeaderVerbose(e) => write!(f, "block header verbose error: {e}"),
+            Error::GetBlockHeaderVerboseV28(e) => write!(f, "block header verbose error: {e}"),
             Error::GetBlockFilter(e) => write!(f, "block filter error: {e}"),
+            Error::GetBlockchainInfo(e) => write!(f, "blockchain info error: {e}"),
+            Error::GetBlockchainInfoV28(e) => write!(f, "blockchain info error: {e}"),
+            Error::GetChainTips(e) => write!(f, "chain tips error: {e}"),
+            Error::GetChainTxStats(e) => write!(f, "chain tx stats error: {e}"),
+            Error::GetRawMempoolVerbose(e) => write!(f, "raw mempool verbose error: {e}"),
+            Error::GetRawTransactionVerbose(e) => write!(f, "raw transaction verbose error: {e}"),
+            Error::CustomTransport => write!(f, "not supported by clients with a custom transport"),
+            Error::InvalidConfig(e) => write!(f, "invalid bitcoind configuration: {e}"),
             Error::InvalidCookieFile => write!(f, "invalid or missing cookie file"),
             Error::InvalidUrl(e) => write!(f, "invalid RPC URL: {e}"),
+            #[cfg(feature = "zmq")]
+            Error::InvalidZmqMessage(e) => write!(f, "invalid ZMQ message: {e}"),
             Error::HexToArray(e) => write!(f, "hash parsing error: {e}"),
             Error::JsonRpc(e) => write!(f, "JSON-RPC error: {e}"),
+            Error::MissingBatchResponse => write!(f, "missing response in batch request"),
+            Error::NetworkMismatch {
+                expected,
+                actual: Some(actual),
+            } => write!(f, "node is on {actual}, expected {expected}"),
+            Error::NetworkMismatch {
+                expected,
+                actual: None,
+            } => write!(f, "node genesis block is not the {expected} one"),
             Error::Json(e) => write!(f, "JSON error: {e}"),
+            Error::Rest(e) => write!(f, "REST error: {e}"),
             Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::SubmitPackage(e) => write!(f, "submit package error: {e}"),
+            Error::TestMempoolAccept(e) => write!(f, "test mempool accept error: {e}"),
+            Error::TxRejected(e) => write!(f, "{e}"),
             Error::TryFromInt(e) => write!(f, "integer conversion overflow: {e}"),
+            Error::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported Bitcoin Core version {v}, the minimum supported version is {}",
+                CoreVersion::MIN_SUPPORTED
+            ),
+            Error::WaitForBlock(e) => write!(f, "wait for block error: {e}"),
+            Error::WaitForBlockHeight(e) => write!(f, "wait for block height error: {e}"),
+            Error::WaitForNewBlock(e) => write!(f, "wait for new block error: {e}"),
+            #[cfg(feature = "zmq")]
+            Error::Zmq(e) => write!(f, "ZMQ error: {e}"),
         }
     }
 }
@@ -117,18 +299,401 @@ impl From<FromHexError> for Error {
     }
 }
 
+/// Error codes returned by `bitcoind`, as defined in Bitcoin Core's `rpc/protocol.h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RpcErrorCode {
+    /// The request object is not a valid JSON-RPC request. (`RPC_INVALID_REQUEST`, `-32600`)
+    InvalidRequest,
+
+    /// The method does not exist or is not available. (`RPC_METHOD_NOT_FOUND`, `-32601`)
+    MethodNotFound,
+
+    /// Invalid method parameters. (`RPC_INVALID_PARAMS`, `-32602`)
+    InvalidParams,
+
+    /// Internal JSON-RPC error. (`RPC_INTERNAL_ERROR`, `-32603`)
+    InternalError,
+
+    /// The request could not be parsed as JSON. (`RPC_PARSE_ERROR`, `-32700`)
+    ParseError,
+
+    /// Exception thrown in command handling. (`RPC_MISC_ERROR`, `-1`)
+    MiscError,
+
+    /// Unexpected type was passed as parameter. (`RPC_TYPE_ERROR`, `-3`)
+    TypeError,
+
+    /// Invalid address or key, also returned when a block or transaction is not found. (`RPC_INVALID_ADDRESS_OR_KEY`, `-5`)
+    InvalidAddressOrKey,
+
+    /// Ran out of memory during operation. (`RPC_OUT_OF_MEMORY`, `-7`)
+    OutOfMemory,
+
+    /// Invalid, missing or duplicate parameter. (`RPC_INVALID_PARAMETER`, `-8`)
+    InvalidParameter,
+
+    /// Database error. (`RPC_DATABASE_ERROR`, `-20`)
+    DatabaseError,
+
+    /// Error parsing or validating structure in raw format. (`RPC_DESERIALIZATION_ERROR`, `-22`)
+    DeserializationError,
+
+    /// General error during transaction or block submission. (`RPC_VERIFY_ERROR`, `-25`)
+    VerifyError,
+
+    /// Transaction or block was rejected by network rules. (`RPC_VERIFY_REJECTED`, `-26`)
+    VerifyRejected,
+
+    /// Transaction is already in the chain. (`RPC_VERIFY_ALREADY_IN_CHAIN`, `-27`)
+    VerifyAlreadyInChain,
+
+    /// Client still warming up. (`RPC_IN_WARMUP`, `-28`)
+    InWarmup,
+
+    /// RPC method is deprecated. (`RPC_METHOD_DEPRECATED`, `-32`)
+    MethodDeprecated,
+
+    /// Bitcoin is not connected. (`RPC_CLIENT_NOT_CONNECTED`, `-9`)
+    ClientNotConnected,
+
+    /// Still downloading initial blocks. (`RPC_CLIENT_IN_INITIAL_DOWNLOAD`, `-10`)
+    ClientInInitialDownload,
+
+    /// Node is already added. (`RPC_CLIENT_NODE_ALREADY_ADDED`, `-23`)
+    ClientNodeAlreadyAdded,
+
+    /// Node has not been added before. (`RPC_CLIENT_NODE_NOT_ADDED`, `-24`)
+    ClientNodeNotAdded,
+
+    /// Node to disconnect not found in connected nodes. (`RPC_CLIENT_NODE_NOT_CONNECTED`, `-29`)
+    ClientNodeNotConnected,
+
+    /// Invalid IP or subnet. (`RPC_CLIENT_INVALID_IP_OR_SUBNET`, `-30`)
+    ClientInvalidIpOrSubnet,
+
+    /// No valid connection manager instance found. (`RPC_CLIENT_P2P_DISABLED`, `-31`)
+    ClientP2pDisabled,
+
+    /// No mempool instance found. (`RPC_CLIENT_MEMPOOL_DISABLED`, `-33`)
+    ClientMempoolDisabled,
+
+    /// Max number of outbound or block-relay connections already open. (`RPC_CLIENT_NODE_CAPACITY_REACHED`, `-34`)
+    ClientNodeCapacityReached,
+
+    /// Unspecified problem with the wallet. (`RPC_WALLET_ERROR`, `-4`)
+    WalletError,
+
+    /// Not enough funds in wallet or account. (`RPC_WALLET_INSUFFICIENT_FUNDS`, `-6`)
+    WalletInsufficientFunds,
+
+    /// Invalid label name. (`RPC_WALLET_INVALID_LABEL_NAME`, `-11`)
+    WalletInvalidLabelName,
+
+    /// Keypool ran out, call `keypoolrefill` first. (`RPC_WALLET_KEYPOOL_RAN_OUT`, `-12`)
+    WalletKeypoolRanOut,
+
+    /// Enter the wallet passphrase with `walletpassphrase` first. (`RPC_WALLET_UNLOCK_NEEDED`, `-13`)
+    WalletUnlockNeeded,
+
+    /// The wallet passphrase entered was incorrect. (`RPC_WALLET_PASSPHRASE_INCORRECT`, `-14`)
+    WalletPassphraseIncorrect,
+
+    /// Command given in wrong wallet encryption state. (`RPC_WALLET_WRONG_ENC_STATE`, `-15`)
+    WalletWrongEncState,
+
+    /// Failed to encrypt the wallet. (`RPC_WALLET_ENCRYPTION_FAILED`, `-16`)
+    WalletEncryptionFailed,
+
+    /// Wallet is already unlocked. (`RPC_WALLET_ALREADY_UNLOCKED`, `-17`)
+    WalletAlreadyUnlocked,
+
+    /// Invalid wallet specified. (`RPC_WALLET_NOT_FOUND`, `-18`)
+    WalletNotFound,
+
+    /// No wallet specified (error when there are multiple wallets loaded). (`RPC_WALLET_NOT_SPECIFIED`, `-19`)
+    WalletNotSpecified,
+
+    /// This same wallet is already loaded. (`RPC_WALLET_ALREADY_LOADED`, `-35`)
+    WalletAlreadyLoaded,
+
+    /// There is already a wallet with the same name. (`RPC_WALLET_ALREADY_EXISTS`, `-36`)
+    WalletAlreadyExists,
+    /// Any other error code.
+    Other(i32),
+}
+
+impl RpcErrorCode {
+    /// Returns the numeric error code.
+    pub fn code(&self) -> i32 {
+        match *self {
+            RpcErrorCode::InvalidRequest => -32600,
+            RpcErrorCode::MethodNotFound => -32601,
+            RpcErrorCode::InvalidParams => -32602,
+            RpcErrorCode::InternalError => -32603,
+            RpcErrorCode::ParseError => -32700,
+            RpcErrorCode::MiscError => -1,
+            RpcErrorCode::TypeError => -3,
+            RpcErrorCode::InvalidAddressOrKey => -5,
+            RpcErrorCode::OutOfMemory => -7,
+            RpcErrorCode::InvalidParameter => -8,
+            RpcErrorCode::DatabaseError => -20,
+            RpcErrorCode::DeserializationError => -22,
+            RpcErrorCode::VerifyError => -25,
+            RpcErrorCode::VerifyRejected => -26,
+            RpcErrorCode::VerifyAlreadyInChain => -27,
+            RpcErrorCode::InWarmup => -28,
+            RpcErrorCode::MethodDeprecated => -32,
+            RpcErrorCode::ClientNotConnected => -9,
+            RpcErrorCode::ClientInInitialDownload => -10,
+            RpcErrorCode::ClientNodeAlreadyAdded => -23,
+            RpcErrorCode::ClientNodeNotAdded => -24,
+            RpcErrorCode::ClientNodeNotConnected => -29,
+            RpcErrorCode::ClientInvalidIpOrSubnet => -30,
+            RpcErrorCode::ClientP2pDisabled => -31,
+            RpcErrorCode::ClientMempoolDisabled => -33,
+            RpcErrorCode::ClientNodeCapacityReached => -34,
+            RpcErrorCode::WalletError => -4,
+            RpcErrorCode::WalletInsufficientFunds => -6,
+            RpcErrorCode::WalletInvalidLabelName => -11,
+            RpcErrorCode::WalletKeypoolRanOut => -12,
+            RpcErrorCode::WalletUnlockNeeded => -13,
+            RpcErrorCode::WalletPassphraseIncorrect => -14,
+            RpcErrorCode::WalletWrongEncState => -15,
+            RpcErrorCode::WalletEncryptionFailed => -16,
+            RpcErrorCode::WalletAlreadyUnlocked => -17,
+            RpcErrorCode::WalletNotFound => -18,
+            RpcErrorCode::WalletNotSpecified => -19,
+            RpcErrorCode::WalletAlreadyLoaded => -35,
+            RpcErrorCode::WalletAlreadyExists => -36,
+            RpcErrorCode::Other(code) => code,
+        }
+    }
+}
+
+impl From<i32> for RpcErrorCode {
+    fn from(code: i32) -> Self {
+        match code {
+            -32600 => RpcErrorCode::InvalidRequest,
+            -32601 => RpcErrorCode::MethodNotFound,
+            -32602 => RpcErrorCode::InvalidParams,
+            -32603 => RpcErrorCode::InternalError,
+            -32700 => RpcErrorCode::ParseError,
+            -1 => RpcErrorCode::MiscError,
+            -3 => RpcErrorCode::TypeError,
+            -5 => RpcErrorCode::InvalidAddressOrKey,
+            -7 => RpcErrorCode::OutOfMemory,
+            -8 => RpcErrorCode::InvalidParameter,
+            -20 => RpcErrorCode::DatabaseError,
+            -22 => RpcErrorCode::DeserializationError,
+            -25 => RpcErrorCode::VerifyError,
+            -26 => RpcErrorCode::VerifyRejected,
+            -27 => RpcErrorCode::VerifyAlreadyInChain,
+            -28 => RpcErrorCode::InWarmup,
+            -32 => RpcErrorCode::MethodDeprecated,
+            -9 => RpcErrorCode::ClientNotConnected,
+            -10 => RpcErrorCode::ClientInInitialDownload,
+            -23 => RpcErrorCode::ClientNodeAlreadyAdded,
+            -24 => RpcErrorCode::ClientNodeNotAdded,
+            -29 => RpcErrorCode::ClientNodeNotConnected,
+            -30 => RpcErrorCode::ClientInvalidIpOrSubnet,
+            -31 => RpcErrorCode::ClientP2pDisabled,
+            -33 => RpcErrorCode::ClientMempoolDisabled,
+            -34 => RpcErrorCode::ClientNodeCapacityReached,
+            -4 => RpcErrorCode::WalletError,
+            -6 => RpcErrorCode::WalletInsufficientFunds,
+            -11 => RpcErrorCode::WalletInvalidLabelName,
+            -12 => RpcErrorCode::WalletKeypoolRanOut,
+            -13 => RpcErrorCode::WalletUnlockNeeded,
+            -14 => RpcErrorCode::WalletPassphraseIncorrect,
+            -15 => RpcErrorCode::WalletWrongEncState,
+            -16 => RpcErrorCode::WalletEncryptionFailed,
+            -17 => RpcErrorCode::WalletAlreadyUnlocked,
+            -18 => RpcErrorCode::WalletNotFound,
+            -19 => RpcErrorCode::WalletNotSpecified,
+            -35 => RpcErrorCode::WalletAlreadyLoaded,
+            -36 => RpcErrorCode::WalletAlreadyExists,
+            code => RpcErrorCode::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for RpcErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
 /// Extension methods for the client error type.
 impl Error {
+    /// Returns the [`RpcErrorCode`] if this is an error returned by `bitcoind`.
+    pub fn rpc_error_code(&self) -> Option<RpcErrorCode> {
+        match self {
+            Error::JsonRpc(jsonrpc::Error::Rpc(rpc_err)) => Some(rpc_err.code.into()),
+            _ => None,
+        }
+    }
+
+    /// Returns the error message if this is an error returned by `bitcoind`.
+    pub fn rpc_error_message(&self) -> Option<&str> {
+        match self {
+            Error::JsonRpc(jsonrpc::Error::Rpc(rpc_err)) => Some(&rpc_err.message),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the request did not get a JSON-RPC response, e.g. because the connection
+    /// to `bitcoind` failed or the server replied with an HTTP error.
+    pub fn is_transport_error(&self) -> bool {
+        matches!(self, Error::JsonRpc(jsonrpc::Error::Transport(_)))
+    }
+
+    /// Returns `true` if the connection to `bitcoind` failed, e.g. because it was refused, reset
+    /// or timed out.
+    ///
+    /// Unlike [`Error::is_transport_error`], HTTP errors, oversized responses and responses that
+    /// failed to parse are not connection errors.
+    pub fn is_connection_error(&self) -> bool {
+        let e = match self {
+            Error::JsonRpc(jsonrpc::Error::Transport(e)) => {
+                // Custom transports may report I/O errors directly.
+                if e.is::<io::Error>() {
+                    return true;
+                }
+                match e.downcast_ref::<bitreq_http::Error>() {
+                    Some(e) => e,
+                    None => return false,
+                }
+            }
+            Error::Rest(e) => e,
+            _ => return false,
+        };
+        matches!(e, bitreq_http::Error::Bitreq(bitreq::Error::IoError(_)))
+    }
+
+    /// Returns the HTTP status code if the server replied with an HTTP error without a JSON-RPC
+    /// response, e.g. `401` for bad credentials or `503` when the work queue is full, or if a
+    /// REST request failed, e.g. `404` for an unknown block.
+    pub fn http_status_code(&self) -> Option<i32> {
+        let e = match self {
+            Error::JsonRpc(jsonrpc::Error::Transport(e)) => {
+                e.downcast_ref::<bitreq_http::Error>()?
+            }
+            Error::Rest(e) => e,
+            _ => return None,
+        };
+        match e {
+            bitreq_http::Error::Http(e) => Some(e.status_code),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if this is a "not found" error returned by `bitcoind`.
     ///
     /// `bitcoind` returns error code `-5` (`RPC_INVALID_ADDRESS_OR_KEY`)
     /// whenever a requested block hash, transaction ID, address, or similar object
     /// does not exist on the node.
     pub fn is_not_found_error(&self) -> bool {
-        if let Error::JsonRpc(jsonrpc::Error::Rpc(rpc_err)) = self {
-            rpc_err.code == -5
-        } else {
-            false
+        self.rpc_error_code() == Some(RpcErrorCode::InvalidAddressOrKey)
+    }
+
+    /// Returns `true` if `bitcoind` is still warming up (`-28`, `RPC_IN_WARMUP`), e.g. loading the
+    /// block index after a restart. The request can be retried later.
+    pub fn is_in_warmup(&self) -> bool {
+        self.rpc_error_code() == Some(RpcErrorCode::InWarmup)
+    }
+
+    /// Returns `true` if a transaction or block was rejected by `bitcoind`, e.g. by mempool policy
+    /// or consensus rules (`-26`, `RPC_VERIFY_REJECTED`).
+    ///
+    /// The reject reason is available with [`Error::rpc_error_message`].
+    pub fn is_rejected(&self) -> bool {
+        self.rpc_error_code() == Some(RpcErrorCode::VerifyRejected)
+    }
+
+    /// Returns `true` if a submitted transaction is already in the chain (`-27`,
+    /// `RPC_VERIFY_ALREADY_IN_CHAIN`).
+    pub fn is_already_in_chain(&self) -> bool {
+        self.rpc_error_code() == Some(RpcErrorCode::VerifyAlreadyInChain)
+    }
+
+    /// Returns `true` if the RPC method does not exist (`-32601`, `RPC_METHOD_NOT_FOUND`), e.g.
+    /// because it is not supported by this version of `bitcoind` or the wallet is disabled.
+    pub fn is_method_not_found(&self) -> bool {
+        self.rpc_error_code() == Some(RpcErrorCode::MethodNotFound)
+    }
+
+    /// Returns `true` if the requested wallet is not loaded (`-18`, `RPC_WALLET_NOT_FOUND`).
+    pub fn is_wallet_not_found(&self) -> bool {
+        self.rpc_error_code() == Some(RpcErrorCode::WalletNotFound)
+    }
+
+    /// Returns `true` if `bitcoind` is still in initial block download (`-10`,
+    /// `RPC_CLIENT_IN_INITIAL_DOWNLOAD`).
+    pub fn is_in_initial_download(&self) -> bool {
+        self.rpc_error_code() == Some(RpcErrorCode::ClientInInitialDownload)
+    }
+}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+
+    fn rpc_error(code: i32) -> Error {
+        Error::JsonRpc(jsonrpc::Error::Rpc(jsonrpc::error::RpcError {
+            code,
+            message: "message".to_string(),
+            data: None,
+        }))
+    }
+
+    #[test]
+    fn test_rpc_error_code_roundtrip() {
+        for code in [-32700, -32601, -28, -27, -26, -18, -5, -1, 42] {
+            assert_eq!(RpcErrorCode::from(code).code(), code);
         }
+        assert_eq!(RpcErrorCode::from(42), RpcErrorCode::Other(42));
+    }
+
+    #[test]
+    fn test_error_predicates() {
+        assert!(rpc_error(-5).is_not_found_error());
+        assert!(rpc_error(-28).is_in_warmup());
+        assert!(rpc_error(-26).is_rejected());
+        assert!(rpc_error(-27).is_already_in_chain());
+        assert!(rpc_error(-32601).is_method_not_found());
+        assert!(rpc_error(-18).is_wallet_not_found());
+        assert!(!rpc_error(-5).is_in_warmup());
+        assert!(!Error::InvalidCookieFile.is_not_found_error());
+
+        assert_eq!(
+            rpc_error(-26).rpc_error_code(),
+            Some(RpcErrorCode::VerifyRejected)
+        );
+        assert_eq!(rpc_error(-26).rpc_error_message(), Some("message"));
+        assert_eq!(Error::InvalidCookieFile.rpc_error_code(), None);
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        let transport_error = |e: bitreq::Error| {
+            Error::JsonRpc(jsonrpc::Error::Transport(Box::new(
+                bitreq_http::Error::Bitreq(e),
+            )))
+        };
+
+        let refused = io::Error::from(io::ErrorKind::ConnectionRefused);
+        assert!(transport_error(bitreq::Error::IoError(refused)).is_connection_error());
+        let timeout = io::Error::from(io::ErrorKind::TimedOut);
+        assert!(Error::JsonRpc(jsonrpc::Error::Transport(Box::new(timeout))).is_connection_error());
+
+        let too_large = transport_error(bitreq::Error::Other(
+            "response body exceeds the maximum size",
+        ));
+        assert!(too_large.is_transport_error());
+        assert!(!too_large.is_connection_error());
+        let json = serde_json::from_str::<u32>("x").unwrap_err();
+        assert!(!transport_error(bitreq::Error::SerdeJsonError(json)).is_connection_error());
+        assert!(!rpc_error(-28).is_connection_error());
     }
 }
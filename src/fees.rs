@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Fee estimation with `estimatesmartfee`.
+
+use core::fmt;
+
+use corepc_types::{bitcoin::FeeRate, v30};
+
+use crate::error::Error;
+
+/// The confirmation targets queried by [`Client::estimate_fee_table`](crate::Client::estimate_fee_table)
+/// when building a typical fee table: next block, 30 minutes, 1 hour and 1 day.
+pub const FEE_TABLE_TARGETS: [u16; 4] = [1, 3, 6, 144];
+
+/// The fee estimate mode of `estimatesmartfee`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EstimateMode {
+    /// Use the default mode of `bitcoind`.
+    Unset,
+    /// Estimate from a shorter history, more responsive to short-term drops in fees.
+    Economical,
+    /// Estimate from a longer history, less likely to be too low to confirm in time.
+    Conservative,
+}
+
+impl EstimateMode {
+    /// Returns the name of the mode as expected by `estimatesmartfee`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EstimateMode::Unset => "unset",
+            EstimateMode::Economical => "economical",
+            EstimateMode::Conservative => "conservative",
+        }
+    }
+}
+
+impl fmt::Display for EstimateMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Converts an `estimatesmartfee` response into a [`FeeRate`].
+///
+/// Returns `Error::FeeEstimateUnavailable` with the `errors` reported by `bitcoind` if there is no
+/// estimate, e.g. because the node has not seen enough blocks yet.
+pub(crate) fn into_fee_rate(estimate: v30::EstimateSmartFee) -> Result<FeeRate, Error> {
+    let estimate = estimate.into_model().map_err(Error::EstimateSmartFee)?;
+    estimate
+        .fee_rate
+        .ok_or_else(|| Error::FeeEstimateUnavailable(estimate.errors.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod test_fees {
+    use super::*;
+
+    #[test]
+    fn test_into_fee_rate() {
+        let estimate = v30::EstimateSmartFee {
+            fee_rate: Some(0.0001),
+            errors: None,
+            blocks: 2,
+        };
+        // 0.0001 BTC/kvB is 10 sat/vB.
+        assert_eq!(
+            into_fee_rate(estimate).unwrap(),
+            FeeRate::from_sat_per_vb_unchecked(10)
+        );
+
+        let estimate = v30::EstimateSmartFee {
+            fee_rate: None,
+            errors: Some(vec!["Insufficient data or no feerate found".to_string()]),
+            blocks: 0,
+        };
+        assert!(matches!(
+            into_fee_rate(estimate),
+            Err(Error::FeeEstimateUnavailable(errors)) if errors.len() == 1
+        ));
+
+        let estimate = v30::EstimateSmartFee {
+            fee_rate: Some(-1.0),
+            errors: None,
+            blocks: 0,
+        };
+        assert!(matches!(
+            into_fee_rate(estimate),
+            Err(Error::EstimateSmartFee(_))
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Rescanning the chain with BIP-0158 compact block filters.
+//!
+//! The [`FilterScanner`] fetches the `basic` filter of each block in a height range, in batches,
+//! and only downloads the blocks whose filter matches one of the scripts of interest. It requires
+//! a node running with `-blockfilterindex=1`.
+//!
+//! The [`FilterHeaderChain`] checks that the filters returned by the node commit to their filter
+//! headers, as described in BIP-0157, to detect a corrupted block filter index.
+
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use corepc_types::bitcoin::{
+    Block, BlockHash, ScriptBuf,
+    bip158::{BlockFilter, FilterHeader},
+    hashes::Hash,
+};
+use corepc_types::model::GetBlockFilter;
+
+use crate::{Client, Error};
+
+/// Number of filters requested per batch request.
+const BATCH_SIZE: u32 = 100;
+
+/// A block whose filter matched one of the scripts of a [`FilterScanner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterMatch {
+    /// Height of the block.
+    pub height: u32,
+    /// The block.
+    pub block: Block,
+}
+
+/// Tracks the filter header chain, verifying that each filter hashes into its claimed header.
+///
+/// The header of a block filter is `sha256d(sha256d(filter) || previous_header)`, where the
+/// previous header of the genesis block is all zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterHeaderChain {
+    /// Header of the last verified filter.
+    header: FilterHeader,
+}
+
+impl FilterHeaderChain {
+    /// Creates a [`FilterHeaderChain`] verifying the filters following the one whose header is
+    /// `prev_header`.
+    pub fn new(prev_header: FilterHeader) -> Self {
+        Self {
+            header: prev_header,
+        }
+    }
+
+    /// Creates a [`FilterHeaderChain`] verifying the filters from the genesis block.
+    pub fn genesis() -> Self {
+        Self::new(FilterHeader::all_zeros())
+    }
+
+    /// Returns the header of the last verified filter.
+    pub fn header(&self) -> FilterHeader {
+        self.header
+    }
+
+    /// Verifies that `filter`, the filter of the block at `height`, hashes into its header, and
+    /// makes it the tip of the chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FilterHeaderMismatch` if the header returned by the node does not match
+    /// the one computed from the filter, in which case the chain is left unchanged.
+    pub fn verify(&mut self, height: u32, filter: &GetBlockFilter) -> Result<(), Error> {
+        let expected = BlockFilter::new(&filter.filter).filter_header(&self.header);
+        let actual = FilterHeader::from_raw_hash(filter.header.to_raw_hash());
+        if expected != actual {
+            return Err(Error::FilterHeaderMismatch {
+                height,
+                expected,
+                actual,
+            });
+        }
+        self.header = actual;
+        Ok(())
+    }
+}
+
+/// Scans a range of blocks for the ones that may involve a set of scripts.
+///
+/// Filters can have false positives, so a matched block may not contain any of the scripts, but
+/// no block containing one of them is skipped.
+#[derive(Debug)]
+pub struct FilterScanner<'a> {
+    /// The client used to query the node.
+    client: &'a Client,
+    /// The scripts to look for.
+    scripts: Vec<ScriptBuf>,
+    /// Height of the next block whose filter is fetched, `None` once the range has been scanned.
+    next_height: Option<u32>,
+    /// Height of the last block to scan.
+    end_height: u32,
+    /// Matched blocks that have not been downloaded yet.
+    matched: VecDeque<(u32, BlockHash)>,
+    /// Whether filters are verified against the filter header chain.
+    verify_headers: bool,
+    /// The filter header chain up to `next_height`, once verification started.
+    headers: Option<FilterHeaderChain>,
+}
+
+impl<'a> FilterScanner<'a> {
+    /// Creates a [`FilterScanner`] looking for `scripts` in the blocks at `heights`.
+    pub fn new(
+        client: &'a Client,
+        scripts: impl IntoIterator<Item = ScriptBuf>,
+        heights: RangeInclusive<u32>,
+    ) -> Self {
+        Self {
+            client,
+            scripts: scripts.into_iter().collect(),
+            next_height: (!heights.is_empty()).then_some(*heights.start()),
+            end_height: *heights.end(),
+            matched: VecDeque::new(),
+            verify_headers: false,
+            headers: None,
+        }
+    }
+
+    /// Verifies each filter against the filter header chain, failing the scan with
+    /// `Error::FilterHeaderMismatch` if the node returns a filter that does not match its header.
+    ///
+    /// The header preceding the range is fetched from the node and trusted, unless the range
+    /// starts at the genesis block. Use [`verify_headers_from`](Self::verify_headers_from) to
+    /// anchor the chain to a known header instead.
+    pub fn verify_headers(mut self) -> Self {
+        self.verify_headers = true;
+        self
+    }
+
+    /// Verifies each filter against the filter header chain, starting from `prev_header`, the
+    /// header of the filter of the block preceding the range.
+    pub fn verify_headers_from(mut self, prev_header: FilterHeader) -> Self {
+        self.verify_headers = true;
+        self.headers = Some(FilterHeaderChain::new(prev_header));
+        self
+    }
+
+    /// Returns the height of the next block whose filter will be checked, or `None` if all the
+    /// filters in the range have been checked.
+    ///
+    /// Matched blocks are only downloaded when returned by [`next_match`](Self::next_match).
+    pub fn next_height(&self) -> Option<u32> {
+        self.next_height
+    }
+
+    /// Returns the next block in the range whose filter matches one of the scripts, or `None` once
+    /// the whole range has been scanned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the underlying RPC calls fail, e.g. if the node has no block
+    /// filter index or the range goes past the tip. The scanner can be resumed by calling this
+    /// method again.
+    pub fn next_match(&mut self) -> Result<Option<FilterMatch>, Error> {
+        loop {
+            if let Some((height, hash)) = self.matched.front().copied() {
+                let block = self.client.get_block(&hash)?;
+                self.matched.pop_front();
+                return Ok(Some(FilterMatch { height, block }));
+            }
+            match self.next_height {
+                Some(height) if !self.scripts.is_empty() => self.scan_batch(height)?,
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// Checks the filters of the batch of blocks starting at `start`, queuing the matching ones.
+    fn scan_batch(&mut self, start: u32) -> Result<(), Error> {
+        let end = self.end_height.min(start.saturating_add(BATCH_SIZE - 1));
+        let heights = start..=end;
+
+        let mut batch = self.client.batch();
+        let calls = heights
+            .clone()
+            .map(|height| batch.get_block_hash(height))
+            .collect::<Vec<_>>();
+        let responses = batch.send()?;
+        let hashes = calls
+            .iter()
+            .map(|call| responses.get(call))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut batch = self.client.batch();
+        let calls = hashes
+            .iter()
+            .map(|hash| batch.get_block_filter(hash))
+            .collect::<Vec<_>>();
+        let responses = batch.send()?;
+
+        let mut headers = match self.headers {
+            Some(headers) => Some(headers),
+            None if self.verify_headers => Some(self.prev_headers(start)?),
+            None => None,
+        };
+        let mut matched = Vec::new();
+        for ((height, hash), call) in heights.zip(hashes).zip(&calls) {
+            let filter = responses.get(call)?;
+            if let Some(headers) = &mut headers {
+                headers.verify(height, &filter)?;
+            }
+            let filter = BlockFilter::new(&filter.filter);
+            let scripts = self.scripts.iter().map(|script| script.as_bytes());
+            if filter.match_any(&hash, scripts).map_err(Error::Bip158)? {
+                matched.push((height, hash));
+            }
+        }
+
+        self.matched.extend(matched);
+        self.headers = headers;
+        self.next_height = end
+            .checked_add(1)
+            .filter(|height| *height <= self.end_height);
+        Ok(())
+    }
+
+    /// Returns the filter header chain ending at the block preceding `height`.
+    fn prev_headers(&self, height: u32) -> Result<FilterHeaderChain, Error> {
+        let Some(prev_height) = height.checked_sub(1) else {
+            return Ok(FilterHeaderChain::genesis());
+        };
+        let prev_hash = self.client.get_block_hash(prev_height)?;
+        let prev_filter = self.client.get_block_filter(&prev_hash)?;
+        Ok(FilterHeaderChain::new(FilterHeader::from_raw_hash(
+            prev_filter.header.to_raw_hash(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test_filter {
+    use corepc_types::bitcoin::bip158::FilterHash;
+
+    use super::*;
+
+    fn block_filter(filter: Vec<u8>, prev_header: &FilterHeader) -> GetBlockFilter {
+        let header = BlockFilter::new(&filter).filter_header(prev_header);
+        GetBlockFilter {
+            filter,
+            header: FilterHash::from_raw_hash(header.to_raw_hash()),
+        }
+    }
+
+    #[test]
+    fn test_filter_header_chain() {
+        let mut headers = FilterHeaderChain::genesis();
+        let first = block_filter(vec![0x01, 0x02], &headers.header());
+        headers.verify(0, &first).unwrap();
+        assert_eq!(headers.header().to_raw_hash(), first.header.to_raw_hash());
+
+        let second = block_filter(vec![0x03], &headers.header());
+        headers.verify(1, &second).unwrap();
+        assert_eq!(headers.header().to_raw_hash(), second.header.to_raw_hash());
+    }
+
+    #[test]
+    fn test_filter_header_chain_mismatch() {
+        let mut headers = FilterHeaderChain::genesis();
+        let mut filter = block_filter(vec![0x01, 0x02], &headers.header());
+        filter.filter = vec![0x01, 0x03];
+
+        let err = headers.verify(7, &filter).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::FilterHeaderMismatch { height: 7, actual, .. }
+                if actual.to_raw_hash() == filter.header.to_raw_hash()
+        ));
+        assert_eq!(headers, FilterHeaderChain::genesis());
+
+        // A filter built on another previous header does not chain either.
+        let other = block_filter(vec![0x01, 0x02], &FilterHeader::hash(b"other"));
+        assert!(headers.verify(0, &other).is_err());
+    }
+}
@@ -6,11 +6,45 @@
 //! It supports multiple authentication methods and provides a type-safe interface for
 //! making RPC calls to a Bitcoin Core daemon.
 
+#[cfg(feature = "async")]
+mod async_client;
+mod batch;
+mod broadcast;
+mod builder;
 mod client;
+mod config;
+mod download;
+mod emitter;
 mod error;
+mod fees;
+mod filter;
+mod mempool;
+mod prune;
+mod rest;
+mod retry;
+#[cfg(feature = "zmq")]
+mod subscriber;
+mod transport;
+mod version;
 
+#[cfg(feature = "async")]
+pub use async_client::AsyncClient;
+pub use batch::{Batch, BatchCall, BatchResponse};
+pub use broadcast::{BroadcastOptions, RejectReason, TxRejection};
+pub use builder::ClientBuilder;
 pub use client::{Auth, Client};
-pub use error::{Error, Result};
+pub use config::RpcConfig;
+pub use download::BlockDownloader;
+pub use emitter::{BlockEvent, Emitter};
+pub use error::{Error, Result, RpcErrorCode};
+pub use fees::{EstimateMode, FEE_TABLE_TARGETS};
+pub use filter::{FilterHeaderChain, FilterMatch, FilterScanner};
+pub use mempool::{MempoolEmitter, MempoolTx, MempoolUpdate, RemovalReason, RemovedTx};
+pub use rest::{GetUtxos, MAX_GETUTXOS_OUTPOINTS, RestClient, Utxo};
+pub use retry::RetryPolicy;
+#[cfg(feature = "zmq")]
+pub use subscriber::{SequenceEvent, ZmqEvent, ZmqMessage, ZmqSubscriber, ZmqTopic};
+pub use version::CoreVersion;
 
 pub use jsonrpc;
 // Re-export corepc_types
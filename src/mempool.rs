@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Incremental mempool synchronization.
+//!
+//! The [`MempoolEmitter`] diffs successive `getrawmempool` snapshots, only downloading
+//! transactions it has not seen before, and classifies every transaction that left the mempool as
+//! confirmed, replaced or evicted.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use corepc_types::bitcoin::{BlockHash, OutPoint, Transaction, Txid};
+
+use crate::{Client, Error};
+
+/// A transaction in the mempool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MempoolTx {
+    /// The transaction.
+    pub tx: Arc<Transaction>,
+    /// UNIX timestamp (in seconds) of the poll in which the transaction was first seen.
+    pub first_seen: u64,
+}
+
+/// Why a transaction left the mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RemovalReason {
+    /// The transaction was confirmed in the block with this hash.
+    Confirmed(BlockHash),
+    /// The transaction was replaced by the mempool transaction with this `Txid`, which spends at
+    /// least one of the same outputs.
+    Replaced(Txid),
+    /// The transaction was evicted (e.g. expired, mempool full or conflicted by a block).
+    Evicted,
+}
+
+/// A transaction that left the mempool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedTx {
+    /// ID of the removed transaction.
+    pub txid: Txid,
+    /// UNIX timestamp (in seconds) of the poll in which the transaction was first seen.
+    pub first_seen: u64,
+    /// Why the transaction left the mempool.
+    pub reason: RemovalReason,
+}
+
+/// Changes to the mempool since the previous [`MempoolEmitter::poll`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MempoolUpdate {
+    /// Transactions that entered the mempool.
+    pub added: Vec<MempoolTx>,
+    /// Transactions that left the mempool.
+    pub removed: Vec<RemovedTx>,
+}
+
+impl MempoolUpdate {
+    /// Returns `true` if the mempool did not change.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Tracks the mempool of a `bitcoind` node across polls.
+#[derive(Debug)]
+pub struct MempoolEmitter<'a> {
+    /// The client used to query the node.
+    client: &'a Client,
+    /// Transactions currently in the mempool.
+    txs: HashMap<Txid, MempoolTx>,
+    /// Height and hash of the best block before the previous snapshot.
+    tip: Option<(u32, BlockHash)>,
+}
+
+impl<'a> MempoolEmitter<'a> {
+    /// Creates a new [`MempoolEmitter`] with no known transactions.
+    ///
+    /// The first [`poll`](Self::poll) reports every transaction in the mempool as added.
+    pub fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            txs: HashMap::new(),
+            tip: None,
+        }
+    }
+
+    /// Returns the transactions currently known to be in the mempool.
+    pub fn txs(&self) -> impl Iterator<Item = (&Txid, &MempoolTx)> {
+        self.txs.iter()
+    }
+
+    /// Returns the transaction with `txid` if it is known to be in the mempool.
+    pub fn get(&self, txid: &Txid) -> Option<&MempoolTx> {
+        self.txs.get(txid)
+    }
+
+    /// Takes a new mempool snapshot and returns the changes since the previous poll.
+    ///
+    /// Only transactions that were not in the previous snapshot are downloaded. Blocks are only
+    /// downloaded when transactions left the mempool, to tell confirmations from evictions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the underlying RPC calls fail. On error, the emitter state is
+    /// left untouched and the next poll starts over from the previous snapshot.
+    pub fn poll(&mut self) -> Result<MempoolUpdate, Error> {
+        // A block connected around the snapshot may or may not have its transactions removed from
+        // it. Removals are checked against the tip read after the snapshot, and the tip read before
+        // it is remembered, so the next poll scans such a block again.
+        let tip = self.best_block()?;
+        let snapshot = self.client.get_raw_mempool()?;
+        let first_seen = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let mut added = Vec::new();
+        for txid in &snapshot {
+            if self.txs.contains_key(txid) {
+                continue;
+            }
+            match self.client.get_raw_transaction(txid) {
+                Ok(tx) => added.push(MempoolTx {
+                    tx: Arc::new(tx),
+                    first_seen,
+                }),
+                // The transaction left the mempool since the snapshot, the next poll won't see it.
+                Err(e) if e.is_not_found_error() => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let snapshot = snapshot.into_iter().collect::<HashSet<_>>();
+        let disappeared = self
+            .txs
+            .keys()
+            .filter(|txid| !snapshot.contains(*txid))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let confirmed = if disappeared.is_empty() {
+            HashMap::new()
+        } else {
+            let (tip_height, tip_hash) = self.best_block()?;
+            self.confirmed_since_last_poll(tip_height, tip_hash)?
+        };
+
+        for tx in added.iter().cloned() {
+            self.txs.insert(tx.tx.compute_txid(), tx);
+        }
+
+        let removed_txs = disappeared
+            .iter()
+            .filter_map(|txid| self.txs.remove(txid).map(|tx| (*txid, tx)))
+            .collect::<Vec<_>>();
+
+        // Outputs spent by transactions still in the mempool, used to detect replacements.
+        let spent = self
+            .txs
+            .iter()
+            .flat_map(|(txid, tx)| {
+                tx.tx
+                    .input
+                    .iter()
+                    .map(move |txin| (txin.previous_output, *txid))
+            })
+            .collect::<HashMap<OutPoint, Txid>>();
+
+        let mut removed = Vec::with_capacity(removed_txs.len());
+        for (txid, tx) in removed_txs {
+            let reason = if let Some(block_hash) = confirmed.get(&txid) {
+                RemovalReason::Confirmed(*block_hash)
+            } else if let Some(replacement) = tx
+                .tx
+                .input
+                .iter()
+                .find_map(|txin| spent.get(&txin.previous_output))
+            {
+                RemovalReason::Replaced(*replacement)
+            } else {
+                RemovalReason::Evicted
+            };
+            removed.push(RemovedTx {
+                txid,
+                first_seen: tx.first_seen,
+                reason,
+            });
+        }
+
+        self.tip = Some(tip);
+
+        Ok(MempoolUpdate { added, removed })
+    }
+
+    /// Returns the height and hash of the best block.
+    fn best_block(&self) -> Result<(u32, BlockHash), Error> {
+        let hash = self.client.get_best_block_hash()?;
+        let height = self.client.get_block_header_verbose(&hash)?.height;
+        Ok((height, hash))
+    }
+
+    /// Returns the `Txid`s confirmed in blocks connected since the previous poll, mapped to the
+    /// hash of the block that confirmed them.
+    ///
+    /// The best chain is walked back from its tip to the common ancestor with the previous tip, so
+    /// blocks connected by a reorg are scanned even if the new chain is not longer.
+    fn confirmed_since_last_poll(
+        &self,
+        tip_height: u32,
+        tip_hash: BlockHash,
+    ) -> Result<HashMap<Txid, BlockHash>, Error> {
+        let mut confirmed = HashMap::new();
+        let Some((mut last_height, mut last_hash)) = self.tip else {
+            return Ok(confirmed);
+        };
+
+        let (mut height, mut hash) = (tip_height, tip_hash);
+        loop {
+            // Step the previous chain back until it is no higher than the best chain.
+            while last_height > height {
+                last_hash = self.client.get_block_header(&last_hash)?.prev_blockhash;
+                last_height -= 1;
+            }
+            if height == last_height && hash == last_hash {
+                break;
+            }
+            let block = self.client.get_block(&hash)?;
+            for tx in &block.txdata {
+                confirmed.insert(tx.compute_txid(), hash);
+            }
+            if height == 0 {
+                break;
+            }
+            (height, hash) = (height - 1, block.header.prev_blockhash);
+        }
+
+        Ok(confirmed)
+    }
+}
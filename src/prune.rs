@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Support for nodes running with `-prune`.
+//!
+//! Pruned nodes delete the data of old blocks, so `getblock` fails for blocks below the prune
+//! height. Such blocks can be downloaded again from a peer with `getblockfrompeer`, see
+//! [`ClientBuilder::fetch_pruned_blocks`](crate::ClientBuilder::fetch_pruned_blocks).
+
+use std::time::Duration;
+
+use corepc_types::bitcoin::BlockHash;
+use jsonrpc::serde::Deserialize;
+
+use crate::error::{Error, RpcErrorCode};
+
+/// Interval between two checks for a block requested from a peer.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The message of the error returned by `getblock` for pruned blocks.
+const PRUNED_DATA: &str = "pruned data";
+
+/// The subset of `getpeerinfo` needed to request blocks from peers.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "jsonrpc::serde")]
+pub(crate) struct PeerId {
+    /// The peer index.
+    pub(crate) id: u64,
+}
+
+/// Returns `Error::BlockPruned` if `error` was returned by `getblock` because the block with the
+/// given hash was pruned, `error` otherwise.
+pub(crate) fn classify_block_error(block_hash: &BlockHash, error: Error) -> Error {
+    let pruned = error.rpc_error_code() == Some(RpcErrorCode::MiscError)
+        && error
+            .rpc_error_message()
+            .is_some_and(|message| message.contains(PRUNED_DATA));
+    if pruned {
+        Error::BlockPruned(*block_hash)
+    } else {
+        error
+    }
+}
+
+#[cfg(test)]
+mod test_prune {
+    use corepc_types::bitcoin::hashes::Hash;
+
+    use super::*;
+
+    fn rpc_error(code: i32, message: &str) -> Error {
+        Error::JsonRpc(jsonrpc::Error::Rpc(jsonrpc::error::RpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }))
+    }
+
+    #[test]
+    fn test_classify_block_error() {
+        let hash = BlockHash::all_zeros();
+
+        let err = classify_block_error(&hash, rpc_error(-1, "Block not available (pruned data)"));
+        assert!(matches!(err, Error::BlockPruned(h) if h == hash));
+
+        let err = classify_block_error(&hash, rpc_error(-5, "Block not found"));
+        assert!(err.is_not_found_error());
+
+        let err = classify_block_error(&hash, rpc_error(-1, "Block not found on disk"));
+        assert_eq!(err.rpc_error_code(), Some(RpcErrorCode::MiscError));
+    }
+}
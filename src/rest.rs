@@ -0,0 +1,348 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Client for the Bitcoin Core REST interface.
+//!
+//! The REST interface is served on the RPC port when `bitcoind` runs with `-rest=1`. It doesn't
+//! require authentication, and serves blocks, headers and filters in binary, which avoids the
+//! overhead of hex and JSON for read-heavy workloads.
+
+use corepc_types::{
+    bitcoin::{
+        Block, BlockHash, OutPoint, TxOut, Txid,
+        bip158::BlockFilter,
+        block::Header,
+        consensus::encode::{self, Decodable, VarInt, deserialize},
+    },
+    model::GetRawMempoolVerbose,
+    v30,
+};
+use jsonrpc::serde_json;
+
+use crate::builder::ClientBuilder;
+use crate::error::Error;
+use crate::transport::HttpTransport;
+
+/// Maximum number of outpoints per [`RestClient::get_utxos`] request.
+pub const MAX_GETUTXOS_OUTPOINTS: usize = 15;
+
+/// Size of a serialized block header.
+const HEADER_SIZE: usize = 80;
+
+/// An unspent transaction output returned by [`RestClient::get_utxos`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utxo {
+    /// Height of the block containing the transaction, or `0x7fffffff` (`MEMPOOL_HEIGHT`) for
+    /// transactions in the mempool.
+    pub height: u32,
+    /// The unspent output.
+    pub txout: TxOut,
+}
+
+/// The result of [`RestClient::get_utxos`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetUtxos {
+    /// Height of the chain tip the outpoints were checked against.
+    pub chain_height: u32,
+    /// Hash of the chain tip the outpoints were checked against.
+    pub chain_tip: BlockHash,
+    /// The unspent output of each requested outpoint, in request order, or `None` if the outpoint
+    /// is spent or doesn't exist.
+    pub utxos: Vec<Option<Utxo>>,
+}
+
+/// Bitcoin Core REST interface client.
+///
+/// Requires `bitcoind` to run with `-rest=1`. Created with [`RestClient::new`], or with
+/// [`ClientBuilder::build_rest`] to configure the timeout and HTTP headers.
+#[derive(Debug)]
+pub struct RestClient {
+    /// The HTTP transport, without credentials.
+    pub(crate) transport: HttpTransport,
+}
+
+impl RestClient {
+    /// Creates a client for the REST interface of the `bitcoind` node at `url`, with a 60 seconds
+    /// timeout.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidUrl` if the URL is not an `http://` or `https://` URL.
+    pub fn new(url: &str) -> Result<Self, Error> {
+        ClientBuilder::new(url).build_rest()
+    }
+
+    /// Sends a `GET` request for `path` and returns the response body.
+    fn get(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.transport.get(path).map_err(Error::Rest)
+    }
+}
+
+/// `bitcoind` REST endpoints implementation for `RestClient`.
+impl RestClient {
+    /// Retrieves the block with the given hash from `/rest/block`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The deserialized `Block` struct.
+    pub fn get_block(&self, block_hash: &BlockHash) -> Result<Block, Error> {
+        deserialize(&self.get_block_bytes(block_hash)?).map_err(Error::Decode)
+    }
+
+    /// Retrieves the consensus-encoded bytes of the block with the given hash from `/rest/block`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The serialized block.
+    pub fn get_block_bytes(&self, block_hash: &BlockHash) -> Result<Vec<u8>, Error> {
+        self.get(&format!("/rest/block/{block_hash}.bin"))
+    }
+
+    /// Retrieves up to `count` headers of the best chain from `/rest/headers`, starting with the
+    /// header of the block with the given hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the first block.
+    /// * `count`: The maximum number of headers, at most 2000.
+    ///
+    /// # Returns
+    ///
+    /// The headers in height order, fewer than `count` if the tip is reached. Empty if the block
+    /// is not in the best chain.
+    pub fn get_headers(&self, block_hash: &BlockHash, count: u32) -> Result<Vec<Header>, Error> {
+        let bytes = self.get(&format!("/rest/headers/{block_hash}.bin?count={count}"))?;
+        bytes
+            .chunks(HEADER_SIZE)
+            .map(|header| deserialize(header).map_err(Error::Decode))
+            .collect()
+    }
+
+    /// Retrieves the `basic` BIP-0158 compact block filter of the block with the given hash from
+    /// `/rest/blockfilter`.
+    ///
+    /// Requires `bitcoind` to run with `-blockfilterindex=1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_hash`: The hash of the block whose filter is requested.
+    ///
+    /// # Returns
+    ///
+    /// The `BlockFilter` of the block.
+    pub fn get_block_filter(&self, block_hash: &BlockHash) -> Result<BlockFilter, Error> {
+        let bytes = self.get(&format!("/rest/blockfilter/basic/{block_hash}.bin"))?;
+        decode_block_filter(&bytes).map_err(Error::Decode)
+    }
+
+    /// Retrieves the `Txid`s of the transactions in the mempool from `/rest/mempool/contents`.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `Txid`s in the mempool.
+    pub fn get_mempool_txids(&self) -> Result<Vec<Txid>, Error> {
+        let bytes = self.get("/rest/mempool/contents.json?verbose=false")?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Retrieves the transactions in the mempool from `/rest/mempool/contents`, with the same
+    /// details as `getrawmempool` with `verbose` set to `true`.
+    ///
+    /// The response schema is the same on all supported Bitcoin Core versions.
+    ///
+    /// # Returns
+    ///
+    /// The mempool entries keyed by `Txid`, as a `GetRawMempoolVerbose` struct.
+    pub fn get_mempool_contents(&self) -> Result<GetRawMempoolVerbose, Error> {
+        let bytes = self.get("/rest/mempool/contents.json")?;
+        serde_json::from_slice::<v30::GetRawMempoolVerbose>(&bytes)?
+            .into_model()
+            .map_err(Error::GetRawMempoolVerbose)
+    }
+
+    /// Looks up unspent outputs in the UTXO set with `/rest/getutxos`.
+    ///
+    /// # Arguments
+    ///
+    /// * `outpoints`: The outputs to look up, at most [`MAX_GETUTXOS_OUTPOINTS`].
+    /// * `check_mempool`: Whether to also consider the mempool, so outputs created by mempool
+    ///   transactions are unspent, and outputs spent by them are spent.
+    ///
+    /// # Returns
+    ///
+    /// The chain tip the outputs were checked against, and the unspent output of each outpoint.
+    pub fn get_utxos(
+        &self,
+        outpoints: &[OutPoint],
+        check_mempool: bool,
+    ) -> Result<GetUtxos, Error> {
+        let mut path = String::from("/rest/getutxos");
+        if check_mempool {
+            path.push_str("/checkmempool");
+        }
+        for outpoint in outpoints {
+            path.push_str(&format!("/{}-{}", outpoint.txid, outpoint.vout));
+        }
+        path.push_str(".bin");
+
+        let bytes = self.get(&path)?;
+        decode_utxos(&bytes, outpoints.len()).map_err(Error::Decode)
+    }
+}
+
+/// Returns `true` if `error` was returned by a REST request for a resource the node may still
+/// serve over JSON-RPC.
+///
+/// `bitcoind` replies `404 Not Found` when it runs without `-rest=1` and for unknown or pruned
+/// blocks, and proxies may reply `403 Forbidden` to paths they don't forward.
+pub(crate) fn should_fall_back(error: &Error) -> bool {
+    matches!(error.http_status_code(), Some(403 | 404))
+}
+
+/// Decodes a block filter serialized as its type, block hash and content.
+fn decode_block_filter(bytes: &[u8]) -> Result<BlockFilter, encode::Error> {
+    let mut reader = bytes;
+    let _filter_type = u8::consensus_decode(&mut reader)?;
+    let _block_hash = BlockHash::consensus_decode(&mut reader)?;
+    let content = Vec::<u8>::consensus_decode(&mut reader)?;
+    if !reader.is_empty() {
+        return Err(encode::Error::ParseFailed("data not consumed entirely"));
+    }
+    Ok(BlockFilter::new(&content))
+}
+
+/// Decodes a `getutxos` response for `len` outpoints, made of the tip height and hash, a bitmap
+/// of the unspent outpoints and the unspent outputs.
+fn decode_utxos(bytes: &[u8], len: usize) -> Result<GetUtxos, encode::Error> {
+    let mut reader = bytes;
+    let chain_height = u32::consensus_decode(&mut reader)?;
+    let chain_tip = BlockHash::consensus_decode(&mut reader)?;
+    let bitmap = Vec::<u8>::consensus_decode(&mut reader)?;
+    let VarInt(count) = VarInt::consensus_decode(&mut reader)?;
+
+    let mut unspent = Vec::new();
+    for _ in 0..count {
+        // Serialized coins start with an unused transaction version.
+        let _version = u32::consensus_decode(&mut reader)?;
+        let height = u32::consensus_decode(&mut reader)?;
+        let txout = TxOut::consensus_decode(&mut reader)?;
+        unspent.push(Utxo { height, txout });
+    }
+    if !reader.is_empty() {
+        return Err(encode::Error::ParseFailed("data not consumed entirely"));
+    }
+
+    let mut unspent = unspent.into_iter();
+    let utxos = (0..len)
+        .map(|i| {
+            let is_unspent = bitmap
+                .get(i / 8)
+                .is_some_and(|byte| byte & (1 << (i % 8)) != 0);
+            if is_unspent { unspent.next() } else { None }
+        })
+        .collect::<Vec<_>>();
+    if unspent.next().is_some() {
+        return Err(encode::Error::ParseFailed(
+            "more outputs than unspent outpoints",
+        ));
+    }
+
+    Ok(GetUtxos {
+        chain_height,
+        chain_tip,
+        utxos,
+    })
+}
+
+#[cfg(test)]
+mod test_rest {
+    use corepc_types::bitcoin::{
+        Amount, ScriptBuf,
+        consensus::encode::{Encodable, serialize},
+        hashes::Hash,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_decode_block_filter() {
+        let content = vec![0x01, 0x02, 0x03];
+        let mut bytes = vec![0x00];
+        bytes.extend(serialize(&BlockHash::all_zeros()));
+        bytes.extend(serialize(&content));
+
+        assert_eq!(decode_block_filter(&bytes).unwrap().content, content);
+
+        bytes.push(0x00);
+        assert!(decode_block_filter(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_should_fall_back() {
+        let http_error = |status_code| {
+            Error::Rest(jsonrpc::bitreq_http::Error::Http(
+                jsonrpc::bitreq_http::HttpError {
+                    status_code,
+                    body: String::new(),
+                },
+            ))
+        };
+
+        assert!(should_fall_back(&http_error(403)));
+        assert!(should_fall_back(&http_error(404)));
+        assert!(!should_fall_back(&http_error(500)));
+        assert!(!should_fall_back(&http_error(503)));
+        assert!(!should_fall_back(&Error::Rest(
+            bitreq::Error::Other("connection reset").into()
+        )));
+    }
+
+    #[test]
+    fn test_decode_utxos() {
+        let txout = TxOut {
+            value: Amount::from_sat(1_000),
+            script_pubkey: ScriptBuf::new(),
+        };
+        let tip = BlockHash::hash(b"tip");
+
+        // Outpoints 0 and 2 of 3 are unspent.
+        let mut bytes = Vec::new();
+        101u32.consensus_encode(&mut bytes).unwrap();
+        tip.consensus_encode(&mut bytes).unwrap();
+        vec![0b101u8].consensus_encode(&mut bytes).unwrap();
+        VarInt(2).consensus_encode(&mut bytes).unwrap();
+        for height in [100u32, 0x7fffffff] {
+            0u32.consensus_encode(&mut bytes).unwrap();
+            height.consensus_encode(&mut bytes).unwrap();
+            txout.consensus_encode(&mut bytes).unwrap();
+        }
+
+        let utxos = decode_utxos(&bytes, 3).unwrap();
+        assert_eq!(utxos.chain_height, 101);
+        assert_eq!(utxos.chain_tip, tip);
+        assert_eq!(
+            utxos.utxos,
+            vec![
+                Some(Utxo {
+                    height: 100,
+                    txout: txout.clone()
+                }),
+                None,
+                Some(Utxo {
+                    height: 0x7fffffff,
+                    txout
+                }),
+            ]
+        );
+
+        // The bitmap doesn't match the number of outputs.
+        assert!(decode_utxos(&bytes, 1).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Retrying RPC calls that fail with transient errors.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::Error;
+
+/// RPC methods that are never retried.
+///
+/// A broadcast that timed out may still have reached the node, so sending it again would fail
+/// with an "already in mempool" or "already in chain" error instead of returning the `Txid`.
+const NOT_RETRIED: &[&str] = &["sendrawtransaction", "submitpackage"];
+
+/// Decides which errors are worth retrying and how long to wait between attempts.
+///
+/// Retries are opt-in, see [`Client::with_retry_policy`](crate::Client::with_retry_policy).
+/// Broadcasts (`sendrawtransaction` and `submitpackage`) are never retried, since a broadcast
+/// that timed out may have reached the node anyway.
+/// The delay before retry `n` is `initial_backoff * 2^(n - 1)`, capped at `max_backoff`. With
+/// jitter enabled, a random delay between half and all of it is used instead, so clients that
+/// lost the same node don't all reconnect at once.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    max_attempts: u32,
+    /// Delay before the first retry.
+    initial_backoff: Duration,
+    /// Upper bound of the delay between attempts.
+    max_backoff: Duration,
+    /// Whether to randomize the delay between attempts.
+    jitter: bool,
+    /// Returns `true` if the call failing with the error should be retried.
+    retry_if: fn(&Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// 5 attempts, backing off from 500ms up to 10s with jitter, retrying the errors matched by
+    /// [`RetryPolicy::is_transient`].
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retry_if: RetryPolicy::is_transient,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default [`RetryPolicy`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the total number of attempts, including the first one.
+    ///
+    /// A value of `0` is treated as `1`, i.e. no retries.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the upper bound of the delay between attempts.
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Enables or disables randomizing the delay between attempts.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the classifier deciding which errors are retried.
+    pub fn retry_if(mut self, retry_if: fn(&Error) -> bool) -> Self {
+        self.retry_if = retry_if;
+        self
+    }
+
+    /// Returns the total number of attempts, including the first one.
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The default classifier.
+    ///
+    /// Retries connection failures (e.g. connection refused or reset while `bitcoind` restarts,
+    /// see [`Error::is_connection_error`]), HTTP errors other than `4xx`, and
+    /// [`Error::is_in_warmup`] responses. Oversized responses and responses that failed to parse
+    /// would fail again, and are not retried.
+    pub fn is_transient(error: &Error) -> bool {
+        match error.http_status_code() {
+            Some(status) => !(400..500).contains(&status),
+            None => error.is_connection_error() || error.is_in_warmup(),
+        }
+    }
+
+    /// Returns `true` if `error`, returned by the `attempt`-th attempt (starting at 1) of a call to
+    /// `method`, should be retried.
+    pub(crate) fn should_retry(&self, method: &str, attempt: u32, error: &Error) -> bool {
+        attempt < self.max_attempts && !NOT_RETRIED.contains(&method) && (self.retry_if)(error)
+    }
+
+    /// Returns how long to wait after the `attempt`-th attempt (starting at 1) failed.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+        if !self.jitter {
+            return backoff;
+        }
+        let half = backoff / 2;
+        let range = (backoff - half).as_nanos() as u64;
+        if range == 0 {
+            return backoff;
+        }
+        half + Duration::from_nanos(random_u64() % (range + 1))
+    }
+}
+
+/// Returns a random number, good enough to spread out retries.
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u128(now.as_nanos());
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test_retry {
+    use super::*;
+
+    fn rpc_error(code: i32) -> Error {
+        Error::JsonRpc(jsonrpc::Error::Rpc(jsonrpc::error::RpcError {
+            code,
+            message: "message".to_string(),
+            data: None,
+        }))
+    }
+
+    fn http_error(status_code: i32) -> Error {
+        let e = jsonrpc::bitreq_http::Error::Http(jsonrpc::bitreq_http::HttpError {
+            status_code,
+            body: String::new(),
+        });
+        Error::JsonRpc(e.into())
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(500))
+            .jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_backoff_jitter() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .jitter(true);
+        for _ in 0..100 {
+            let backoff = policy.backoff(2);
+            assert!(backoff >= Duration::from_millis(100));
+            assert!(backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::new().max_attempts(3);
+        assert!(policy.should_retry("getblock", 1, &rpc_error(-28)));
+        assert!(policy.should_retry("getblock", 2, &rpc_error(-28)));
+        assert!(!policy.should_retry("getblock", 3, &rpc_error(-28)));
+        assert!(!policy.should_retry("getblock", 1, &rpc_error(-5)));
+        assert!(!policy.should_retry("sendrawtransaction", 1, &rpc_error(-28)));
+        assert!(!policy.should_retry("submitpackage", 1, &rpc_error(-28)));
+
+        let policy = policy.retry_if(|_| false);
+        assert!(!policy.should_retry("getblock", 1, &rpc_error(-28)));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(RetryPolicy::is_transient(&rpc_error(-28)));
+        assert!(RetryPolicy::is_transient(&http_error(503)));
+        assert!(!RetryPolicy::is_transient(&http_error(401)));
+        assert!(!RetryPolicy::is_transient(&rpc_error(-26)));
+        assert!(!RetryPolicy::is_transient(&Error::InvalidCookieFile));
+
+        let bitreq_error = |e| Error::JsonRpc(jsonrpc::bitreq_http::Error::Bitreq(e).into());
+        let reset = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        assert!(RetryPolicy::is_transient(&bitreq_error(
+            bitreq::Error::IoError(reset)
+        )));
+        assert!(!RetryPolicy::is_transient(&bitreq_error(
+            bitreq::Error::Other("response body exceeds the maximum size")
+        )));
+    }
+}
@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Subscriber for the ZMQ notifications published by `bitcoind`.
+//!
+//! `bitcoind` publishes notifications on the endpoints configured with `-zmqpub<topic>=<address>`.
+//! The [`ZmqSubscriber`] receives the `hashblock`, `rawtx` and `sequence` notifications, and uses
+//! the sequence number of each message to report the messages dropped since the previous one of
+//! the same topic, e.g. because the subscriber was too slow and the high water mark was reached.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use corepc_types::{
+    bitcoin::{BlockHash, Transaction, Txid, consensus::encode::deserialize, hashes::Hash},
+    v30::GetZmqNotifications,
+};
+
+use crate::{Client, Error};
+
+/// A notification topic published by `bitcoind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZmqTopic {
+    /// The hash of each new best block (`-zmqpubhashblock`).
+    HashBlock,
+    /// Each transaction added to the mempool or in a connected block (`-zmqpubrawtx`).
+    RawTx,
+    /// Block connections and disconnections, and mempool additions and removals
+    /// (`-zmqpubsequence`).
+    Sequence,
+}
+
+impl ZmqTopic {
+    /// Returns the topic name, which prefixes every message of the topic.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZmqTopic::HashBlock => "hashblock",
+            ZmqTopic::RawTx => "rawtx",
+            ZmqTopic::Sequence => "sequence",
+        }
+    }
+
+    /// Returns the topic of a notification type reported by `getzmqnotifications`, e.g.
+    /// `pubhashblock`, or `None` if the type is not supported.
+    pub fn from_notification_type(type_: &str) -> Option<Self> {
+        match type_ {
+            "pubhashblock" => Some(ZmqTopic::HashBlock),
+            "pubrawtx" => Some(ZmqTopic::RawTx),
+            "pubsequence" => Some(ZmqTopic::Sequence),
+            _ => None,
+        }
+    }
+
+    /// Returns the topic whose name is `name`.
+    fn from_name(name: &[u8]) -> Option<Self> {
+        [ZmqTopic::HashBlock, ZmqTopic::RawTx, ZmqTopic::Sequence]
+            .into_iter()
+            .find(|topic| topic.as_str().as_bytes() == name)
+    }
+}
+
+/// An event of the `sequence` topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceEvent {
+    /// A block was connected to the best chain.
+    BlockConnected(BlockHash),
+    /// A block was disconnected from the best chain.
+    BlockDisconnected(BlockHash),
+    /// A transaction was added to the mempool.
+    TxAdded {
+        /// The added transaction.
+        txid: Txid,
+        /// The mempool sequence number after the addition, see `getrawmempool`.
+        mempool_sequence: u64,
+    },
+    /// A transaction was removed from the mempool, for a reason other than block inclusion.
+    TxRemoved {
+        /// The removed transaction.
+        txid: Txid,
+        /// The mempool sequence number after the removal, see `getrawmempool`.
+        mempool_sequence: u64,
+    },
+}
+
+/// The content of a ZMQ notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZmqEvent {
+    /// A new best block, from the `hashblock` topic.
+    HashBlock(BlockHash),
+    /// A transaction, from the `rawtx` topic.
+    RawTx(Transaction),
+    /// An event from the `sequence` topic.
+    Sequence(SequenceEvent),
+}
+
+impl ZmqEvent {
+    /// Returns the topic the event was published on.
+    pub fn topic(&self) -> ZmqTopic {
+        match self {
+            ZmqEvent::HashBlock(_) => ZmqTopic::HashBlock,
+            ZmqEvent::RawTx(_) => ZmqTopic::RawTx,
+            ZmqEvent::Sequence(_) => ZmqTopic::Sequence,
+        }
+    }
+}
+
+/// A notification received by a [`ZmqSubscriber`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZmqMessage {
+    /// The decoded notification.
+    pub event: ZmqEvent,
+    /// The sequence number of the message, incremented by `bitcoind` for every message of the
+    /// topic published on the endpoint.
+    pub sequence: u32,
+    /// The number of messages of the same topic and endpoint dropped since the previous one, `0`
+    /// if the sequence number went back, e.g. because `bitcoind` restarted.
+    pub missed: u32,
+}
+
+/// Receives the ZMQ notifications published by `bitcoind`.
+pub struct ZmqSubscriber {
+    /// The ZMQ context the sockets belong to.
+    context: zmq::Context,
+    /// The endpoints and their `SUB` sockets.
+    sockets: Vec<(String, zmq::Socket)>,
+    /// The last sequence number received, by socket index and topic.
+    sequences: HashMap<(usize, ZmqTopic), u32>,
+    /// Index of the socket read first by the next receive, so all sockets are read fairly.
+    next_socket: usize,
+}
+
+impl std::fmt::Debug for ZmqSubscriber {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let endpoints = self.sockets.iter().map(|(endpoint, _)| endpoint);
+        f.debug_struct("ZmqSubscriber")
+            .field("endpoints", &endpoints.collect::<Vec<_>>())
+            .field("sequences", &self.sequences)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ZmqSubscriber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZmqSubscriber {
+    /// Creates a [`ZmqSubscriber`] without any subscription.
+    pub fn new() -> Self {
+        Self {
+            context: zmq::Context::new(),
+            sockets: Vec::new(),
+            sequences: HashMap::new(),
+            next_socket: 0,
+        }
+    }
+
+    /// Creates a [`ZmqSubscriber`] subscribed to the supported notifications in `notifications`,
+    /// as returned by [`Client::get_zmq_notifications`].
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if none of the notifications is supported.
+    /// * Returns `Error::Zmq` if connecting to an endpoint fails.
+    pub fn from_notifications(notifications: &[GetZmqNotifications]) -> Result<Self, Error> {
+        let mut subscriber = Self::new();
+        for notification in notifications {
+            if let Some(topic) = ZmqTopic::from_notification_type(&notification.type_) {
+                subscriber.subscribe(topic, &notification.address)?;
+            }
+        }
+        if subscriber.sockets.is_empty() {
+            return Err(Error::InvalidConfig(
+                "bitcoind publishes no supported ZMQ notification".to_owned(),
+            ));
+        }
+        Ok(subscriber)
+    }
+
+    /// Creates a [`ZmqSubscriber`] subscribed to the supported notifications published by the node
+    /// `client` is connected to.
+    ///
+    /// The endpoints are the addresses `bitcoind` binds to, so a node publishing on a wildcard
+    /// address such as `tcp://0.0.0.0:28332` must be subscribed to with
+    /// [`subscribe`](Self::subscribe) instead.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if the node publishes none of the supported notifications.
+    /// * Returns `Error::Zmq` if connecting to an endpoint fails.
+    /// * Returns errors from the `getzmqnotifications` RPC call.
+    pub fn discover(client: &Client) -> Result<Self, Error> {
+        Self::from_notifications(&client.get_zmq_notifications()?)
+    }
+
+    /// Subscribes to the `topic` notifications published on `endpoint`, e.g.
+    /// `tcp://127.0.0.1:28332`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::Zmq` if connecting to the endpoint fails.
+    pub fn subscribe(&mut self, topic: ZmqTopic, endpoint: &str) -> Result<(), Error> {
+        let socket = match self.sockets.iter().find(|(e, _)| e == endpoint) {
+            Some((_, socket)) => socket,
+            None => {
+                let socket = self.context.socket(zmq::SUB).map_err(Error::Zmq)?;
+                socket.connect(endpoint).map_err(Error::Zmq)?;
+                self.sockets.push((endpoint.to_owned(), socket));
+                &self.sockets[self.sockets.len() - 1].1
+            }
+        };
+        socket
+            .set_subscribe(topic.as_str().as_bytes())
+            .map_err(Error::Zmq)
+    }
+
+    /// Blocks until the next notification is received.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if there is no subscription.
+    /// * Returns `Error::Zmq` if receiving fails.
+    /// * Returns `Error::InvalidZmqMessage` or `Error::Decode` if the message is malformed.
+    pub fn recv(&mut self) -> Result<ZmqMessage, Error> {
+        loop {
+            if let Some(message) = self.poll(-1)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for the next notification, returning `None` if none was received.
+    ///
+    /// # Errors
+    ///
+    /// * Returns `Error::InvalidConfig` if there is no subscription.
+    /// * Returns `Error::Zmq` if receiving fails.
+    /// * Returns `Error::InvalidZmqMessage` or `Error::Decode` if the message is malformed.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<ZmqMessage>, Error> {
+        self.poll(timeout.as_millis().try_into().unwrap_or(i64::MAX))
+    }
+
+    /// Waits up to `timeout_ms` milliseconds, or forever if negative, for a message on any of
+    /// the sockets.
+    fn poll(&mut self, timeout_ms: i64) -> Result<Option<ZmqMessage>, Error> {
+        if self.sockets.is_empty() {
+            return Err(Error::InvalidConfig("no ZMQ subscription".to_owned()));
+        }
+        let mut items = self
+            .sockets
+            .iter()
+            .map(|(_, socket)| socket.as_poll_item(zmq::POLLIN))
+            .collect::<Vec<_>>();
+        if zmq::poll(&mut items, timeout_ms).map_err(Error::Zmq)? == 0 {
+            return Ok(None);
+        }
+
+        let len = self.sockets.len();
+        let Some(index) = (0..len)
+            .map(|i| (self.next_socket + i) % len)
+            .find(|i| items[*i].is_readable())
+        else {
+            return Ok(None);
+        };
+        drop(items);
+        self.next_socket = (index + 1) % len;
+
+        let frames = self.sockets[index]
+            .1
+            .recv_multipart(0)
+            .map_err(Error::Zmq)?;
+        let (event, sequence) = decode_message(&frames)?;
+        let last = self.sequences.insert((index, event.topic()), sequence);
+        let missed = missed_messages(last, sequence);
+
+        Ok(Some(ZmqMessage {
+            event,
+            sequence,
+            missed,
+        }))
+    }
+}
+
+/// Returns the number of messages dropped between the messages with sequence numbers `last` and
+/// `sequence`.
+///
+/// `bitcoind` numbers the messages from `0` again when it restarts, so a sequence number that
+/// didn't increase starts a new count.
+fn missed_messages(last: Option<u32>, sequence: u32) -> u32 {
+    match last {
+        Some(last) if sequence > last => sequence - last - 1,
+        _ => 0,
+    }
+}
+
+/// Decodes a notification made of the topic, body and sequence number frames.
+fn decode_message(frames: &[Vec<u8>]) -> Result<(ZmqEvent, u32), Error> {
+    let [topic, body, sequence] = frames else {
+        return Err(invalid(format!("expected 3 frames, got {}", frames.len())));
+    };
+    let topic = ZmqTopic::from_name(topic)
+        .ok_or_else(|| invalid(format!("unknown topic {}", String::from_utf8_lossy(topic))))?;
+    let sequence = <[u8; 4]>::try_from(sequence.as_slice())
+        .map(u32::from_le_bytes)
+        .map_err(|_| invalid("invalid sequence number"))?;
+
+    let event = match topic {
+        ZmqTopic::HashBlock => ZmqEvent::HashBlock(BlockHash::from_byte_array(hash(body)?)),
+        ZmqTopic::RawTx => ZmqEvent::RawTx(deserialize(body).map_err(Error::Decode)?),
+        ZmqTopic::Sequence => ZmqEvent::Sequence(decode_sequence(body)?),
+    };
+    Ok((event, sequence))
+}
+
+/// Decodes the body of a `sequence` notification: a hash, a label and, for mempool events, the
+/// mempool sequence number.
+fn decode_sequence(body: &[u8]) -> Result<SequenceEvent, Error> {
+    let (hash_bytes, rest) = body.split_at(body.len().min(32));
+    let hash = hash(hash_bytes)?;
+    let mempool_sequence = || {
+        <[u8; 8]>::try_from(&rest[1..])
+            .map(u64::from_le_bytes)
+            .map_err(|_| invalid("invalid mempool sequence number"))
+    };
+
+    match rest.first() {
+        Some(b'C') if rest.len() == 1 => Ok(SequenceEvent::BlockConnected(
+            BlockHash::from_byte_array(hash),
+        )),
+        Some(b'D') if rest.len() == 1 => Ok(SequenceEvent::BlockDisconnected(
+            BlockHash::from_byte_array(hash),
+        )),
+        Some(b'A') => Ok(SequenceEvent::TxAdded {
+            txid: Txid::from_byte_array(hash),
+            mempool_sequence: mempool_sequence()?,
+        }),
+        Some(b'R') => Ok(SequenceEvent::TxRemoved {
+            txid: Txid::from_byte_array(hash),
+            mempool_sequence: mempool_sequence()?,
+        }),
+        _ => Err(invalid("invalid sequence label")),
+    }
+}
+
+/// Returns the internal byte order of a hash, which `bitcoind` publishes reversed.
+fn hash(bytes: &[u8]) -> Result<[u8; 32], Error> {
+    let mut hash = <[u8; 32]>::try_from(bytes).map_err(|_| invalid("invalid hash length"))?;
+    hash.reverse();
+    Ok(hash)
+}
+
+/// Returns an `Error::InvalidZmqMessage` with the given reason.
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidZmqMessage(reason.into())
+}
+
+#[cfg(test)]
+mod test_subscriber {
+    use corepc_types::bitcoin::Network;
+    use corepc_types::bitcoin::{blockdata::constants::genesis_block, consensus::serialize};
+
+    use super::*;
+
+    /// Returns the bytes of `hash` in the order `bitcoind` publishes them.
+    fn reversed(hash: [u8; 32]) -> Vec<u8> {
+        hash.into_iter().rev().collect()
+    }
+
+    #[test]
+    fn test_decode_hashblock() {
+        let hash = genesis_block(Network::Bitcoin).block_hash();
+        let frames = vec![
+            b"hashblock".to_vec(),
+            reversed(hash.to_byte_array()),
+            7u32.to_le_bytes().to_vec(),
+        ];
+
+        let (event, sequence) = decode_message(&frames).unwrap();
+
+        assert_eq!(event, ZmqEvent::HashBlock(hash));
+        assert_eq!(sequence, 7);
+        // `bitcoind` publishes the hash in the displayed byte order.
+        assert!(frames[1].starts_with(&[0x00, 0x00, 0x00, 0x00]));
+    }
+
+    #[test]
+    fn test_decode_rawtx() {
+        let tx = genesis_block(Network::Bitcoin).txdata[0].clone();
+        let frames = vec![
+            b"rawtx".to_vec(),
+            serialize(&tx),
+            0u32.to_le_bytes().to_vec(),
+        ];
+
+        let (event, _) = decode_message(&frames).unwrap();
+
+        assert_eq!(event, ZmqEvent::RawTx(tx));
+    }
+
+    #[test]
+    fn test_missed_messages() {
+        assert_eq!(missed_messages(None, 5), 0);
+        assert_eq!(missed_messages(Some(4), 5), 0);
+        assert_eq!(missed_messages(Some(2), 5), 2);
+        // `bitcoind` restarted.
+        assert_eq!(missed_messages(Some(5), 0), 0);
+        assert_eq!(missed_messages(Some(5), 5), 0);
+        assert_eq!(missed_messages(Some(u32::MAX), 0), 0);
+    }
+
+    #[test]
+    fn test_decode_sequence() {
+        let hash = [0x11; 32];
+        let frame = |body: Vec<u8>| vec![b"sequence".to_vec(), body, 1u32.to_le_bytes().to_vec()];
+
+        let mut body = reversed(hash);
+        body.push(b'C');
+        let (event, _) = decode_message(&frame(body)).unwrap();
+        assert_eq!(
+            event,
+            ZmqEvent::Sequence(SequenceEvent::BlockConnected(BlockHash::from_byte_array(
+                hash
+            )))
+        );
+
+        let mut body = reversed(hash);
+        body.push(b'A');
+        body.extend(42u64.to_le_bytes());
+        let (event, _) = decode_message(&frame(body)).unwrap();
+        assert_eq!(
+            event,
+            ZmqEvent::Sequence(SequenceEvent::TxAdded {
+                txid: Txid::from_byte_array(hash),
+                mempool_sequence: 42,
+            })
+        );
+
+        // Mempool events must carry a mempool sequence number.
+        let mut body = reversed(hash);
+        body.push(b'R');
+        assert!(decode_message(&frame(body)).is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid_message() {
+        let sequence = 0u32.to_le_bytes().to_vec();
+
+        assert!(decode_message(&[b"hashblock".to_vec(), vec![0; 32]]).is_err());
+        assert!(decode_message(&[b"hashtx".to_vec(), vec![0; 32], sequence.clone()]).is_err());
+        assert!(decode_message(&[b"hashblock".to_vec(), vec![0; 31], sequence]).is_err());
+        assert!(decode_message(&[b"hashblock".to_vec(), vec![0; 32], vec![0; 3]]).is_err());
+    }
+}
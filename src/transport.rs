@@ -0,0 +1,361 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The HTTP transport used by [`Client`](crate::Client).
+
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use jsonrpc::{
+    Request, Response, Transport,
+    bitreq_http::{self, HttpError},
+    serde,
+};
+
+use crate::client::Auth;
+
+/// Capacity reserved for a response body before reading it, in bytes.
+const INITIAL_BODY_CAPACITY: usize = 64 * 1024;
+
+/// A blocking HTTP transport built on top of [`bitreq`].
+///
+/// Unlike [`jsonrpc::bitreq_http::BitreqHttpTransport`], it supports custom headers and a limit
+/// on the response size. Errors are reported as [`bitreq_http::Error`]s, so they can be matched
+/// the same way regardless of the transport.
+///
+/// When authenticating with a cookie file, the cookie is read again if the server replies with
+/// `401 Unauthorized`, since `bitcoind` writes a fresh one on every restart.
+#[derive(Debug)]
+pub(crate) struct HttpTransport {
+    /// URL of the RPC server.
+    pub(crate) url: String,
+    /// Timeout applied to every request.
+    pub(crate) timeout: Duration,
+    /// The value of the `Authorization` HTTP header, if any.
+    pub(crate) authorization: RwLock<Option<String>>,
+    /// Path of the cookie file the credentials were read from, if any.
+    pub(crate) cookie_file: Option<PathBuf>,
+    /// Additional headers sent with every request.
+    pub(crate) headers: Vec<(String, String)>,
+    /// Maximum size of a response body in bytes.
+    pub(crate) max_response_size: Option<usize>,
+}
+
+impl HttpTransport {
+    /// Posts `body` as JSON with the given `timeout` and parses the JSON response, retrying once
+    /// with fresh credentials if they were rejected and the cookie file changed.
+    fn request<R>(
+        &self,
+        body: impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<R, bitreq_http::Error>
+    where
+        R: for<'de> serde::Deserialize<'de>,
+    {
+        match self.send(&body, timeout) {
+            Err(bitreq_http::Error::Http(HttpError {
+                status_code: 401, ..
+            })) if self.reload_cookie() => self.send(&body, timeout),
+            result => result,
+        }
+    }
+
+    /// Reads the cookie file again, returning `true` if the credentials changed.
+    fn reload_cookie(&self) -> bool {
+        let Some(path) = &self.cookie_file else {
+            return false;
+        };
+        let Ok(authorization) = Auth::CookieFile(path.clone()).basic_auth() else {
+            return false;
+        };
+        let mut current = self
+            .authorization
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        if *current == authorization {
+            return false;
+        }
+        *current = authorization;
+        true
+    }
+
+    /// Posts `body` as JSON with the given `timeout` and parses the JSON response.
+    fn send<R>(
+        &self,
+        body: &impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<R, bitreq_http::Error>
+    where
+        R: for<'de> serde::Deserialize<'de>,
+    {
+        let resp = self.post(body, timeout)?.send_lazy()?;
+        let status_code = resp.status_code;
+        let body = self.read_body(resp)?;
+        parse_response(status_code, &body)
+    }
+
+    /// Creates a request posting `body` as JSON with the given `timeout`, the headers and the
+    /// credentials of this transport.
+    fn post(
+        &self,
+        body: &impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<bitreq::Request, bitreq_http::Error> {
+        let mut req = bitreq::post(&self.url)
+            .with_timeout(timeout_secs(timeout))
+            .with_headers(self.headers.iter().cloned())
+            .with_json(body)?;
+        let authorization = self
+            .authorization
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(authorization) = authorization {
+            req = req.with_header("Authorization", authorization);
+        }
+        Ok(req)
+    }
+
+    /// Sends a `GET` request for `path`, relative to the server URL, and returns the response
+    /// body.
+    ///
+    /// Used for the REST interface, which doesn't require authentication.
+    pub(crate) fn get(&self, path: &str) -> Result<Vec<u8>, bitreq_http::Error> {
+        let resp = self.get_request(path).send_lazy()?;
+        let status_code = resp.status_code;
+        let body = self.read_body(resp)?;
+        check_status(status_code, body)
+    }
+
+    /// Creates a `GET` request for `path` with the timeout and the headers of this transport.
+    fn get_request(&self, path: &str) -> bitreq::Request {
+        bitreq::get(format!("{}{path}", self.url))
+            .with_timeout(timeout_secs(self.timeout))
+            .with_headers(self.headers.iter().cloned())
+    }
+
+    /// Sends `req`, waiting up to `timeout` for the response instead of the timeout of the
+    /// transport.
+    ///
+    /// Used by long polls, which the node only answers after a delay.
+    pub(crate) fn send_request_with_timeout(
+        &self,
+        req: Request,
+        timeout: Duration,
+    ) -> Result<Response, jsonrpc::Error> {
+        Ok(self.request(req, timeout)?)
+    }
+
+    /// Reads the body of `resp`, failing if it is larger than the maximum response size.
+    fn read_body(&self, resp: bitreq::ResponseLazy) -> Result<Vec<u8>, bitreq::Error> {
+        let max = self.max_response_size.unwrap_or(usize::MAX);
+        // The advertised length is not trusted for the initial allocation, the body grows as
+        // it is read.
+        let mut body = Vec::with_capacity(INITIAL_BODY_CAPACITY.min(max));
+        // Read one byte past the maximum to tell a body of the maximum size from a larger one.
+        let limit = u64::try_from(max).unwrap_or(u64::MAX).saturating_add(1);
+        Read::take(resp, limit)
+            .read_to_end(&mut body)
+            .map_err(|e| match e.downcast::<bitreq::Error>() {
+                Ok(e) => e,
+                Err(e) => bitreq::Error::IoError(e),
+            })?;
+        if body.len() > max {
+            return Err(bitreq::Error::Other(
+                "response body exceeds the maximum size",
+            ));
+        }
+        Ok(body)
+    }
+}
+
+/// Parses the JSON `body` of a response with the given `status_code`.
+fn parse_response<R>(status_code: i32, body: &[u8]) -> Result<R, bitreq_http::Error>
+where
+    R: for<'de> serde::Deserialize<'de>,
+{
+    // Like `bitreq_http`, return the raw HTTP error if the body is not a JSON-RPC response, for
+    // instance if the bitcoind HTTP server work queue depth is exceeded.
+    match jsonrpc::serde_json::from_slice(body) {
+        Ok(json) => Ok(json),
+        Err(_) if status_code != 200 => Err(bitreq_http::Error::Http(HttpError {
+            status_code,
+            body: String::from_utf8_lossy(body).into_owned(),
+        })),
+        Err(e) => Err(bitreq::Error::SerdeJsonError(e).into()),
+    }
+}
+
+/// Returns the `body` of a response to a `GET` request, or an HTTP error if the `status_code` is
+/// not `200 OK`.
+fn check_status(status_code: i32, body: Vec<u8>) -> Result<Vec<u8>, bitreq_http::Error> {
+    if status_code != 200 {
+        return Err(bitreq_http::Error::Http(HttpError {
+            status_code,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        }));
+    }
+    Ok(body)
+}
+
+/// Returns `timeout` in seconds.
+///
+/// `bitreq` only supports second granularity, round up so short timeouts don't disable it.
+pub(crate) fn timeout_secs(timeout: Duration) -> u64 {
+    let round_up = u64::from(timeout.subsec_nanos() > 0);
+    timeout.as_secs().saturating_add(round_up)
+}
+
+/// Asynchronous requests, sent by [`AsyncClient`](crate::AsyncClient).
+///
+/// Like [`bitreq::Request::send_async`], the response body is read on the current task once the
+/// headers are received.
+#[cfg(feature = "async")]
+impl HttpTransport {
+    /// Posts `body` as JSON with the given `timeout` and parses the JSON response, retrying once
+    /// with fresh credentials if they were rejected and the cookie file changed.
+    pub(crate) async fn request_async<R>(
+        &self,
+        body: impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<R, bitreq_http::Error>
+    where
+        R: for<'de> serde::Deserialize<'de>,
+    {
+        match self.send_async(&body, timeout).await {
+            Err(bitreq_http::Error::Http(HttpError {
+                status_code: 401, ..
+            })) if self.reload_cookie() => self.send_async(&body, timeout).await,
+            result => result,
+        }
+    }
+
+    /// Posts `body` as JSON with the given `timeout` and parses the JSON response.
+    async fn send_async<R>(
+        &self,
+        body: &impl serde::Serialize,
+        timeout: Duration,
+    ) -> Result<R, bitreq_http::Error>
+    where
+        R: for<'de> serde::Deserialize<'de>,
+    {
+        let resp = self.post(body, timeout)?.send_lazy_async().await?;
+        let status_code = resp.status_code;
+        let body = self.read_body(resp)?;
+        parse_response(status_code, &body)
+    }
+
+    /// Sends a `GET` request for `path`, relative to the server URL, and returns the response
+    /// body.
+    pub(crate) async fn get_async(&self, path: &str) -> Result<Vec<u8>, bitreq_http::Error> {
+        let resp = self.get_request(path).send_lazy_async().await?;
+        let status_code = resp.status_code;
+        let body = self.read_body(resp)?;
+        check_status(status_code, body)
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send_request(&self, req: Request) -> Result<Response, jsonrpc::Error> {
+        Ok(self.request(req, self.timeout)?)
+    }
+
+    fn send_batch(&self, reqs: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        Ok(self.request(reqs, self.timeout)?)
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+/// An [`HttpTransport`] shared with the [`Client`](crate::Client) whose JSON-RPC client uses it,
+/// so the client can send requests with another timeout.
+#[derive(Debug)]
+pub(crate) struct SharedTransport(pub(crate) Arc<HttpTransport>);
+
+impl Transport for SharedTransport {
+    fn send_request(&self, req: Request) -> Result<Response, jsonrpc::Error> {
+        self.0.send_request(req)
+    }
+
+    fn send_batch(&self, reqs: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        self.0.send_batch(reqs)
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_target(f)
+    }
+}
+
+#[cfg(test)]
+mod test_transport {
+    use super::*;
+
+    #[test]
+    fn test_reload_cookie() {
+        let path = std::env::temp_dir().join(format!("bdk-cookie-{}", std::process::id()));
+        std::fs::write(&path, "__cookie__:first").unwrap();
+        let auth = Auth::CookieFile(path.clone());
+        let transport = HttpTransport {
+            url: "http://127.0.0.1:18443".to_owned(),
+            timeout: Duration::from_secs(1),
+            authorization: RwLock::new(auth.basic_auth().unwrap()),
+            cookie_file: Some(path.clone()),
+            headers: Vec::new(),
+            max_response_size: None,
+        };
+
+        // The cookie did not change.
+        assert!(!transport.reload_cookie());
+
+        std::fs::write(&path, "__cookie__:second").unwrap();
+        assert!(transport.reload_cookie());
+        assert_eq!(
+            *transport.authorization.read().unwrap(),
+            auth.basic_auth().unwrap()
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(!transport.reload_cookie());
+    }
+
+    #[test]
+    fn test_read_body_max_size() {
+        use std::io::Write;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = vec![b'x'; 100_000];
+        let server_body = body.clone();
+        let server = std::thread::spawn(move || {
+            for _ in 0..3 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    server_body.len()
+                );
+                // The client may hang up before reading the whole body.
+                let _ = stream.write_all(header.as_bytes());
+                let _ = stream.write_all(&server_body);
+            }
+        });
+
+        let transport = |max_response_size| HttpTransport {
+            url: url.clone(),
+            timeout: Duration::from_secs(5),
+            authorization: RwLock::new(None),
+            cookie_file: None,
+            headers: Vec::new(),
+            max_response_size,
+        };
+        assert_eq!(transport(None).get("/").unwrap(), body);
+        assert_eq!(transport(Some(body.len())).get("/").unwrap(), body);
+        assert!(transport(Some(body.len() - 1)).get("/").is_err());
+
+        server.join().unwrap();
+    }
+}
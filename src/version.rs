@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Bitcoin Core version detection.
+
+use core::fmt;
+
+use jsonrpc::serde::Deserialize;
+
+use crate::Error;
+
+/// A Bitcoin Core release with a distinct JSON-RPC schema supported by this crate.
+///
+/// The version is detected at runtime from `getnetworkinfo`, and used by the client to deserialize
+/// responses into the matching [`corepc_types`] version-specific types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum CoreVersion {
+    /// Bitcoin Core v28.x.
+    V28,
+    /// Bitcoin Core v29.x.
+    V29,
+    /// Bitcoin Core v30.x and newer.
+    V30,
+}
+
+impl CoreVersion {
+    /// The oldest `version` reported by `getnetworkinfo` this crate can talk to (v28.0.0).
+    pub const MIN_SUPPORTED: usize = 28_00_00;
+
+    /// Maps the numeric `version` reported by `getnetworkinfo` (e.g. `290100` for v29.1.0) to a
+    /// [`CoreVersion`].
+    ///
+    /// Releases newer than the latest known one are treated as the latest known one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedVersion` if `version` is older than
+    /// [`CoreVersion::MIN_SUPPORTED`].
+    pub fn from_server_version(version: usize) -> Result<Self, Error> {
+        match version / 1_00_00 {
+            ..28 => Err(Error::UnsupportedVersion(version)),
+            28 => Ok(CoreVersion::V28),
+            29 => Ok(CoreVersion::V29),
+            _ => Ok(CoreVersion::V30),
+        }
+    }
+}
+
+impl fmt::Display for CoreVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoreVersion::V28 => write!(f, "v28"),
+            CoreVersion::V29 => write!(f, "v29"),
+            CoreVersion::V30 => write!(f, "v30"),
+        }
+    }
+}
+
+/// The subset of `getnetworkinfo` needed to detect the server version.
+///
+/// The full response changes between releases, so only the stable `version` field is parsed.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "jsonrpc::serde")]
+pub(crate) struct NetworkInfoVersion {
+    /// The server version.
+    pub(crate) version: usize,
+}
+
+#[cfg(test)]
+mod test_version {
+    use super::*;
+
+    #[test]
+    fn test_from_server_version() {
+        assert_eq!(
+            CoreVersion::from_server_version(280000).unwrap(),
+            CoreVersion::V28
+        );
+        assert_eq!(
+            CoreVersion::from_server_version(280200).unwrap(),
+            CoreVersion::V28
+        );
+        assert_eq!(
+            CoreVersion::from_server_version(290100).unwrap(),
+            CoreVersion::V29
+        );
+        assert_eq!(
+            CoreVersion::from_server_version(300000).unwrap(),
+            CoreVersion::V30
+        );
+        assert_eq!(
+            CoreVersion::from_server_version(310000).unwrap(),
+            CoreVersion::V30
+        );
+    }
+
+    #[test]
+    fn test_from_server_version_unsupported() {
+        assert!(matches!(
+            CoreVersion::from_server_version(270100),
+            Err(Error::UnsupportedVersion(270100))
+        ));
+    }
+}
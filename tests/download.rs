@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for [`Client::fetch_blocks`].
+
+use bdk_bitcoind_client::{Client, Error, RpcErrorCode};
+
+mod testenv;
+
+use testenv::TestEnv;
+
+#[test]
+fn test_fetch_blocks_in_height_order() {
+    let env = TestEnv::setup().unwrap();
+    let mut expected = vec![env.client.get_block_hash(0).unwrap()];
+    expected.extend(env.mine_blocks(200, None).expect("failed to mine blocks"));
+
+    let blocks = env
+        .client
+        .fetch_blocks(0..=200, 4)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to fetch blocks");
+
+    let heights = blocks.iter().map(|(height, _)| *height).collect::<Vec<_>>();
+    assert_eq!(heights, (0..=200).collect::<Vec<_>>());
+    let hashes = blocks
+        .iter()
+        .map(|(_, block)| block.block_hash())
+        .collect::<Vec<_>>();
+    assert_eq!(hashes, expected);
+}
+
+#[test]
+fn test_fetch_blocks_stops_on_first_error() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    // Heights 11 and 12 are past the tip.
+    let mut blocks = env.client.fetch_blocks(5..=12, 4).unwrap();
+    for height in 5..=10 {
+        let (h, _block) = blocks.next().unwrap().expect("block is in the chain");
+        assert_eq!(h, height);
+    }
+    assert_eq!(blocks.next_height(), Some(11));
+    let err = blocks.next().unwrap().unwrap_err();
+    assert_eq!(err.rpc_error_code(), Some(RpcErrorCode::InvalidParameter));
+    assert!(blocks.next().is_none());
+    assert_eq!(blocks.next_height(), None);
+}
+
+#[test]
+fn test_fetch_blocks_empty_range() {
+    let env = TestEnv::setup().unwrap();
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let mut blocks = env.client.fetch_blocks(1..=0, 4).unwrap();
+
+    assert!(blocks.next().is_none());
+}
+
+#[test]
+fn test_fetch_blocks_requires_builder() {
+    let transport = jsonrpc::bitreq_http::Builder::new()
+        .url("http://127.0.0.1:18443")
+        .unwrap()
+        .build();
+    let client = Client::with_transport(transport);
+
+    assert!(matches!(
+        client.fetch_blocks(0..=10, 4),
+        Err(Error::CustomTransport)
+    ));
+}
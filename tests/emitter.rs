@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for the `bdk_bitcoind_client` [`Emitter`].
+
+use bdk_bitcoind_client::{BlockEvent, Emitter, Error};
+
+mod testenv;
+
+use testenv::TestEnv;
+
+#[test]
+fn test_emitter_emits_blocks_in_order() {
+    let env = TestEnv::setup().unwrap();
+
+    let genesis_hash = env.client.get_block_hash(0).unwrap();
+    let mined = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    let mut emitter = Emitter::new(&env.client, 0, genesis_hash);
+    let mut emitted = Vec::new();
+    while let Some(event) = emitter.next_block().expect("failed to emit block") {
+        match event {
+            BlockEvent::Connected { height, block } => {
+                assert_eq!(height as usize, emitted.len() + 1);
+                emitted.push(block.block_hash());
+            }
+            BlockEvent::Disconnected { .. } => panic!("unexpected disconnected block"),
+        }
+    }
+
+    assert_eq!(emitted, mined);
+    assert_eq!(emitter.tip(), (10, mined[9]));
+}
+
+#[test]
+fn test_emitter_detects_reorg() {
+    let env = TestEnv::setup().unwrap();
+
+    let genesis_hash = env.client.get_block_hash(0).unwrap();
+    let original = env.mine_blocks(5, None).expect("failed to mine blocks");
+
+    let mut emitter = Emitter::new(&env.client, 0, genesis_hash);
+    while emitter
+        .next_block()
+        .expect("failed to emit block")
+        .is_some()
+    {}
+    assert_eq!(emitter.tip(), (5, original[4]));
+
+    // Reorg out the last 2 blocks and replace them with a longer chain.
+    env.invalidate_block(original[3])
+        .expect("failed to invalidate block");
+    let replacement = env.mine_blocks(3, None).expect("failed to mine blocks");
+
+    let mut events = Vec::new();
+    while let Some(event) = emitter.next_block().expect("failed to emit block") {
+        events.push(event);
+    }
+
+    assert_eq!(events.len(), 5);
+    assert_eq!(
+        events[0],
+        BlockEvent::Disconnected {
+            height: 5,
+            hash: original[4]
+        }
+    );
+    assert_eq!(
+        events[1],
+        BlockEvent::Disconnected {
+            height: 4,
+            hash: original[3]
+        }
+    );
+    for (i, event) in events[2..].iter().enumerate() {
+        match event {
+            BlockEvent::Connected { height, block } => {
+                assert_eq!(*height as usize, 4 + i);
+                assert_eq!(block.block_hash(), replacement[i]);
+            }
+            BlockEvent::Disconnected { .. } => panic!("unexpected disconnected block"),
+        }
+    }
+    assert_eq!(emitter.tip(), (6, replacement[2]));
+}
+
+#[test]
+fn test_emitter_rejects_checkpoint_with_wrong_height() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(3, None).expect("failed to mine blocks");
+
+    // A checkpoint at a wrong height must not be emitted from, even if the block was reorged out.
+    env.invalidate_block(hashes[2])
+        .expect("failed to invalidate block");
+    for height in [0, 2, 4] {
+        let mut emitter = Emitter::new(&env.client, height, hashes[2]);
+        let err = emitter.next_block().unwrap_err();
+        let Error::CheckpointMismatch {
+            hash,
+            height: checkpoint_height,
+            actual,
+        } = err
+        else {
+            panic!("expected a checkpoint mismatch, got {err:?}");
+        };
+        assert_eq!((hash, checkpoint_height, actual), (hashes[2], height, 3));
+        assert_eq!(emitter.tip(), (height, hashes[2]));
+    }
+}
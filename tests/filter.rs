@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for the `bdk_bitcoind_client` [`FilterScanner`].
+
+use bdk_bitcoind_client::corepc_types::bitcoin::{bip158::FilterHeader, hashes::Hash};
+use bdk_bitcoind_client::{Error, FilterScanner};
+
+mod testenv;
+
+use testenv::TestEnv;
+
+#[test]
+fn test_filter_scanner_finds_blocks_paying_to_script() {
+    let env = TestEnv::setup().unwrap();
+    let address = env.bitcoind.client.new_address().unwrap();
+    let script = address.script_pubkey();
+
+    let _hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+    let expected = env
+        .mine_blocks(3, Some(address))
+        .expect("failed to mine blocks");
+    let _hashes = env.mine_blocks(200, None).expect("failed to mine blocks");
+    let tip = env.client.get_block_count().unwrap();
+
+    let mut scanner = FilterScanner::new(&env.client, [script.clone()], 0..=tip);
+    let mut matched = Vec::new();
+    while let Some(m) = scanner.next_match().expect("failed to scan") {
+        // Filters can have false positives, only keep blocks actually paying to the script.
+        if m.block.txdata[0]
+            .output
+            .iter()
+            .any(|txout| txout.script_pubkey == script)
+        {
+            matched.push((m.height, m.block.block_hash()));
+        }
+    }
+
+    assert_eq!(
+        matched,
+        vec![(11, expected[0]), (12, expected[1]), (13, expected[2])]
+    );
+    assert_eq!(scanner.next_height(), None);
+}
+
+#[test]
+fn test_filter_scanner_without_scripts() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    let mut scanner = FilterScanner::new(&env.client, [], 0..=10);
+
+    assert!(scanner.next_match().unwrap().is_none());
+}
+
+#[test]
+fn test_filter_scanner_verifies_filter_headers() {
+    let env = TestEnv::setup().unwrap();
+    let address = env.bitcoind.client.new_address().unwrap();
+    let script = address.script_pubkey();
+
+    let _hashes = env
+        .mine_blocks(20, Some(address))
+        .expect("failed to mine blocks");
+    let tip = env.client.get_block_count().unwrap();
+
+    // From the genesis block, the chain starts at the all-zeros header.
+    let mut scanner = FilterScanner::new(&env.client, [script.clone()], 0..=tip).verify_headers();
+    while scanner
+        .next_match()
+        .expect("filter headers must chain")
+        .is_some()
+    {}
+
+    // From a later block, the previous header is fetched from the node.
+    let mut scanner = FilterScanner::new(&env.client, [script.clone()], 5..=tip).verify_headers();
+    while scanner
+        .next_match()
+        .expect("filter headers must chain")
+        .is_some()
+    {}
+
+    // Anchoring the chain to the wrong header fails at the first filter.
+    let wrong_header = FilterHeader::hash(b"not the filter header of block 5");
+    let mut scanner =
+        FilterScanner::new(&env.client, [script], 6..=tip).verify_headers_from(wrong_header);
+    assert!(matches!(
+        scanner.next_match(),
+        Err(Error::FilterHeaderMismatch { height: 6, .. })
+    ));
+    assert_eq!(scanner.next_height(), Some(6));
+}
@@ -0,0 +1,254 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for the `bdk_bitcoind_client` [`MempoolEmitter`].
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bdk_bitcoind_client::{Auth, Client, MempoolEmitter, RemovalReason};
+use corepc_types::bitcoin::{Amount, Txid};
+use jsonrpc::bitreq_http::BitreqHttpTransport;
+use jsonrpc::serde_json::{self, json};
+use jsonrpc::{Request, Response, Transport};
+
+mod testenv;
+
+use testenv::TestEnv;
+
+/// A transport running a hook after each request, to interleave node changes with the calls of a
+/// poll.
+struct HookTransport {
+    /// The transport requests are forwarded to.
+    inner: BitreqHttpTransport,
+    /// Called with the method of each request once it got a response.
+    hook: Box<dyn Fn(&str) + Send + Sync>,
+}
+
+impl Transport for HookTransport {
+    fn send_request(&self, request: Request) -> Result<Response, jsonrpc::Error> {
+        let method = request.method.to_owned();
+        let response = self.inner.send_request(request);
+        (self.hook)(&method);
+        response
+    }
+
+    fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        self.inner.send_batch(requests)
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt_target(f)
+    }
+}
+
+#[test]
+fn test_mempool_emitter_tracks_added_and_confirmed() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+
+    let mut emitter = MempoolEmitter::new(&env.client);
+    assert!(emitter.poll().expect("failed to poll mempool").is_empty());
+
+    let address = env.bitcoind.client.new_address().unwrap();
+    let txid = env
+        .bitcoind
+        .client
+        .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+        .expect("failed to send to address")
+        .into_model()
+        .unwrap()
+        .txid;
+
+    let update = emitter.poll().expect("failed to poll mempool");
+    assert_eq!(update.added.len(), 1);
+    assert_eq!(update.added[0].tx.compute_txid(), txid);
+    assert!(update.removed.is_empty());
+    let first_seen = update.added[0].first_seen;
+
+    // Polling again without changes must not re-download anything.
+    assert!(emitter.poll().expect("failed to poll mempool").is_empty());
+
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+    let update = emitter.poll().expect("failed to poll mempool");
+    assert!(update.added.is_empty());
+    assert_eq!(update.removed.len(), 1);
+    assert_eq!(update.removed[0].txid, txid);
+    assert_eq!(update.removed[0].first_seen, first_seen);
+    assert_eq!(
+        update.removed[0].reason,
+        RemovalReason::Confirmed(block_hash)
+    );
+}
+
+#[test]
+fn test_mempool_emitter_detects_replacement() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+
+    let address = env.bitcoind.client.new_address().unwrap();
+    let txid = env
+        .bitcoind
+        .client
+        .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+        .expect("failed to send to address")
+        .into_model()
+        .unwrap()
+        .txid;
+
+    let mut emitter = MempoolEmitter::new(&env.client);
+    let update = emitter.poll().expect("failed to poll mempool");
+    assert_eq!(update.added.len(), 1);
+
+    let bumped: serde_json::Value = env
+        .bitcoind
+        .client
+        .call("bumpfee", &[json!(txid)])
+        .expect("failed to bump fee");
+    let replacement: Txid = bumped["txid"].as_str().unwrap().parse().unwrap();
+
+    let update = emitter.poll().expect("failed to poll mempool");
+    assert_eq!(update.added.len(), 1);
+    assert_eq!(update.added[0].tx.compute_txid(), replacement);
+    assert_eq!(update.removed.len(), 1);
+    assert_eq!(update.removed[0].txid, txid);
+    assert_eq!(
+        update.removed[0].reason,
+        RemovalReason::Replaced(replacement)
+    );
+}
+
+/// Returns a client connected to the node of `env` that mines a block right after the next call
+/// to `method` once armed, and the flag arming it.
+fn hooked_client(env: &TestEnv, method: &'static str) -> (Client, Arc<AtomicBool>) {
+    let url = env.bitcoind.rpc_url();
+    let (user, pass) = Auth::CookieFile(env.bitcoind.params.cookie_file.clone())
+        .get_user_pass()
+        .unwrap();
+    let transport = || {
+        jsonrpc::bitreq_http::Builder::new()
+            .url(&url)
+            .unwrap()
+            .basic_auth(user.clone().unwrap(), pass.clone())
+            .build()
+    };
+
+    let armed = Arc::new(AtomicBool::new(false));
+    let miner = Client::with_transport(transport());
+    let address = env.bitcoind.client.new_address().unwrap().to_string();
+    let hook_armed = Arc::clone(&armed);
+    let client = Client::with_transport(HookTransport {
+        inner: transport(),
+        hook: Box::new(move |m| {
+            if m == method && hook_armed.swap(false, Ordering::SeqCst) {
+                let _hashes: Vec<String> = miner
+                    .call("generatetoaddress", &[json!(1), json!(address)])
+                    .expect("failed to mine block");
+            }
+        }),
+    });
+
+    (client, armed)
+}
+
+/// Sends a transaction from the wallet of the node, returning its `Txid`.
+fn send_tx(env: &TestEnv) -> Txid {
+    let address = env.bitcoind.client.new_address().unwrap();
+    env.bitcoind
+        .client
+        .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+        .expect("failed to send to address")
+        .into_model()
+        .unwrap()
+        .txid
+}
+
+#[test]
+fn test_mempool_emitter_block_between_tip_and_snapshot() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    // The tip is read with `getbestblockhash` and `getblockheader` before the snapshot.
+    let (client, armed) = hooked_client(&env, "getblockheader");
+    let mut emitter = MempoolEmitter::new(&client);
+    let txid = send_tx(&env);
+    assert_eq!(
+        emitter.poll().expect("failed to poll mempool").added.len(),
+        1
+    );
+
+    armed.store(true, Ordering::SeqCst);
+    let update = emitter.poll().expect("failed to poll mempool");
+    assert!(!armed.load(Ordering::SeqCst), "no block was mined");
+
+    // The transaction is missing from the snapshot, but the block confirming it is scanned.
+    let block_hash = env.client.get_best_block_hash().unwrap();
+    assert_eq!(update.removed.len(), 1);
+    assert_eq!(update.removed[0].txid, txid);
+    assert_eq!(
+        update.removed[0].reason,
+        RemovalReason::Confirmed(block_hash)
+    );
+}
+
+#[test]
+fn test_mempool_emitter_block_after_snapshot() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let (client, armed) = hooked_client(&env, "getrawmempool");
+    let mut emitter = MempoolEmitter::new(&client);
+    let txid = send_tx(&env);
+    assert_eq!(
+        emitter.poll().expect("failed to poll mempool").added.len(),
+        1
+    );
+
+    // The transaction is still in the snapshot, and is confirmed by the next poll.
+    armed.store(true, Ordering::SeqCst);
+    assert!(emitter.poll().expect("failed to poll mempool").is_empty());
+    assert!(!armed.load(Ordering::SeqCst), "no block was mined");
+    let update = emitter.poll().expect("failed to poll mempool");
+
+    let block_hash = env.client.get_best_block_hash().unwrap();
+    assert_eq!(update.removed.len(), 1);
+    assert_eq!(update.removed[0].txid, txid);
+    assert_eq!(
+        update.removed[0].reason,
+        RemovalReason::Confirmed(block_hash)
+    );
+}
+
+#[test]
+fn test_mempool_emitter_confirmed_below_previous_tip() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(110, None).expect("failed to mine blocks");
+    // Spend a coinbase that stays mature after the reorg.
+    let coinbase = env.client.get_block(&hashes[0]).unwrap().txdata[0].clone();
+    let tx = env
+        .create_signed_child_tx(&coinbase, Amount::from_sat(10_000))
+        .expect("failed to create transaction");
+    let txid = env
+        .client
+        .send_raw_transaction(&tx)
+        .expect("failed to broadcast transaction");
+
+    let mut emitter = MempoolEmitter::new(&env.client);
+    assert_eq!(
+        emitter.poll().expect("failed to poll mempool").added.len(),
+        1
+    );
+
+    // Replace the last 3 blocks with a shorter chain confirming the transaction.
+    env.invalidate_block(hashes[107])
+        .expect("failed to invalidate block");
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+    assert_eq!(env.client.get_block_count().unwrap(), 108);
+
+    let update = emitter.poll().expect("failed to poll mempool");
+    assert_eq!(update.removed.len(), 1);
+    assert_eq!(update.removed[0].txid, txid);
+    assert_eq!(
+        update.removed[0].reason,
+        RemovalReason::Confirmed(block_hash)
+    );
+}
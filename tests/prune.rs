@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for fetching blocks from a pruned node.
+
+use core::str::FromStr;
+use std::time::{Duration, Instant};
+
+use bdk_bitcoind_client::{Auth, ClientBuilder, Error};
+use corepc_types::bitcoin::BlockHash;
+use jsonrpc::serde_json::json;
+
+mod testenv;
+
+use testenv::TestEnv;
+
+/// Mines enough blocks to fill several block files, and prunes the ones below `height`.
+///
+/// Returns the hashes of the mined blocks and the prune height.
+fn mine_and_prune(env: &TestEnv, height: u32) -> (Vec<BlockHash>, u32) {
+    let hashes = env.mine_blocks(1000, None).expect("failed to mine blocks");
+    let _pruned: u32 = env
+        .client
+        .call("pruneblockchain", &[json!(height)])
+        .expect("failed to prune");
+    let prune_height = env
+        .client
+        .prune_height()
+        .expect("failed to get prune height")
+        .expect("node is pruned");
+    (hashes, prune_height)
+}
+
+#[test]
+fn test_prune_height() {
+    let env = TestEnv::setup().unwrap();
+    assert_eq!(env.client.prune_height().unwrap(), None);
+
+    let env = TestEnv::setup_pruned().unwrap();
+    let (_hashes, prune_height) = mine_and_prune(&env, 500);
+
+    assert!(prune_height > 1);
+    assert!(prune_height <= 501);
+}
+
+#[test]
+fn test_get_block_pruned() {
+    let env = TestEnv::setup_pruned().unwrap();
+    let (hashes, prune_height) = mine_and_prune(&env, 500);
+
+    let err = env.client.get_block(&hashes[0]).unwrap_err();
+    assert!(matches!(err, Error::BlockPruned(hash) if hash == hashes[0]));
+
+    // Blocks above the prune height are still available.
+    let hash = hashes[prune_height as usize];
+    assert_eq!(env.client.get_block(&hash).unwrap().block_hash(), hash);
+
+    // Unknown blocks are not reported as pruned.
+    let unknown =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+            .unwrap();
+    assert!(
+        env.client
+            .get_block(&unknown)
+            .unwrap_err()
+            .is_not_found_error()
+    );
+}
+
+#[test]
+fn test_fetch_pruned_block_without_peers() {
+    let env = TestEnv::setup_pruned().unwrap();
+    let (hashes, _prune_height) = mine_and_prune(&env, 500);
+    let client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .fetch_pruned_blocks(Duration::from_secs(30))
+        .build()
+        .unwrap();
+
+    // No peer can serve the block, so the fallback gives up without waiting.
+    let start = Instant::now();
+    let err = client.get_block(&hashes[0]).unwrap_err();
+
+    assert!(matches!(err, Error::BlockPruned(_)));
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_get_block_pruned() {
+    let env = TestEnv::setup_pruned().unwrap();
+    let client = env.async_client().unwrap();
+    let (hashes, prune_height) = mine_and_prune(&env, 500);
+
+    assert_eq!(client.prune_height().await.unwrap(), Some(prune_height));
+    let err = client.get_block(&hashes[0]).await.unwrap_err();
+    assert!(matches!(err, Error::BlockPruned(_)));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_fetch_pruned_block_without_peers() {
+    let env = TestEnv::setup_pruned().unwrap();
+    let client = env
+        .async_client()
+        .unwrap()
+        .with_pruned_block_fetching(Duration::from_secs(30));
+    let (hashes, _prune_height) = mine_and_prune(&env, 500);
+
+    let start = Instant::now();
+    let err = client.get_block(&hashes[0]).await.unwrap_err();
+
+    assert!(matches!(err, Error::BlockPruned(_)));
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for the `bdk_bitcoind_client` [`RestClient`].
+
+use core::str::FromStr;
+
+use bdk_bitcoind_client::{ClientBuilder, RestClient};
+use corepc_types::bitcoin::{Amount, BlockHash, OutPoint, Txid};
+
+mod testenv;
+
+use testenv::TestEnv;
+
+/// Creates a [`RestClient`] for the node of `env`.
+fn rest_client(env: &TestEnv) -> RestClient {
+    RestClient::new(&env.bitcoind.rpc_url()).expect("failed to create REST client")
+}
+
+#[test]
+fn test_rest_get_block() {
+    let env = TestEnv::setup().unwrap();
+    let rest = rest_client(&env);
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+    let block = rest.get_block(&block_hash).expect("failed to get block");
+    let bytes = rest
+        .get_block_bytes(&block_hash)
+        .expect("failed to get block bytes");
+
+    assert_eq!(block, env.client.get_block(&block_hash).unwrap());
+    assert_eq!(bytes, env.client.get_block_bytes(&block_hash).unwrap());
+}
+
+#[test]
+fn test_rest_get_block_unknown() {
+    let env = TestEnv::setup().unwrap();
+    let rest = rest_client(&env);
+    let unknown =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+            .unwrap();
+
+    let err = rest.get_block(&unknown).unwrap_err();
+
+    assert_eq!(err.http_status_code(), Some(404));
+}
+
+#[test]
+fn test_rest_get_headers() {
+    let env = TestEnv::setup().unwrap();
+    let rest = rest_client(&env);
+    let hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    let headers = rest
+        .get_headers(&hashes[2], 5)
+        .expect("failed to get headers");
+    let header_hashes = headers.iter().map(|h| h.block_hash()).collect::<Vec<_>>();
+    assert_eq!(header_hashes, hashes[2..7]);
+    assert_eq!(headers[0], env.client.get_block_header(&hashes[2]).unwrap());
+
+    // Fewer headers are returned once the tip is reached.
+    let headers = rest
+        .get_headers(&hashes[8], 5)
+        .expect("failed to get headers");
+    assert_eq!(headers.len(), 2);
+}
+
+#[test]
+fn test_rest_get_block_filter() {
+    let env = TestEnv::setup().unwrap();
+    let rest = rest_client(&env);
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+    let filter = rest
+        .get_block_filter(&block_hash)
+        .expect("failed to get block filter");
+
+    assert_eq!(
+        filter.content,
+        env.client.get_block_filter(&block_hash).unwrap().filter
+    );
+}
+
+#[test]
+fn test_rest_get_mempool() {
+    let env = TestEnv::setup().unwrap();
+    let rest = rest_client(&env);
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let address = env.bitcoind.client.new_address().unwrap();
+    let txid = env
+        .bitcoind
+        .client
+        .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+        .unwrap()
+        .txid()
+        .unwrap();
+
+    let txids = rest
+        .get_mempool_txids()
+        .expect("failed to get mempool txids");
+    assert_eq!(txids, vec![txid]);
+
+    let contents = rest
+        .get_mempool_contents()
+        .expect("failed to get mempool contents");
+    assert_eq!(contents.0.keys().collect::<Vec<_>>(), vec![&txid]);
+}
+
+#[test]
+fn test_rest_get_utxos() {
+    let env = TestEnv::setup().unwrap();
+    let rest = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .user_agent("bdk")
+        .build_rest()
+        .unwrap();
+    let address = env.bitcoind.client.new_address().unwrap();
+    let hashes = env
+        .mine_blocks(1, Some(address.clone()))
+        .expect("failed to mine block");
+    let _hashes = env.mine_blocks(100, None).expect("failed to mine blocks");
+
+    let coinbase = env.client.get_block(&hashes[0]).unwrap().txdata[0].compute_txid();
+    let unknown =
+        Txid::from_str("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+    let outpoints = [OutPoint::new(unknown, 0), OutPoint::new(coinbase, 0)];
+
+    let utxos = rest
+        .get_utxos(&outpoints, false)
+        .expect("failed to get utxos");
+
+    assert_eq!(utxos.chain_height, 101);
+    assert_eq!(utxos.chain_tip, env.client.get_best_block_hash().unwrap());
+    assert_eq!(utxos.utxos.len(), 2);
+    assert!(utxos.utxos[0].is_none());
+    let utxo = utxos.utxos[1].as_ref().expect("coinbase output is unspent");
+    assert_eq!(utxo.height, 1);
+    assert_eq!(utxo.txout.script_pubkey, address.script_pubkey());
+}
@@ -5,13 +5,19 @@
 //! These tests require a running Bitcoin Core node in regtest mode. To setup, refer to [`bitcoind`].
 
 use core::str::FromStr;
+use std::time::{Duration, Instant};
 
-use bdk_bitcoind_client::{Auth, Client, Error};
-use corepc_types::bitcoin::{Amount, BlockHash, Txid};
+use bdk_bitcoind_client::{
+    Auth, BroadcastOptions, Client, ClientBuilder, CoreVersion, Error, EstimateMode,
+    FEE_TABLE_TARGETS, RejectReason, RetryPolicy, TxRejection,
+};
+use corepc_types::bitcoin::{Amount, BlockHash, FeeRate, Network, Txid, consensus::serialize};
+use corepc_types::model::ChainTipsStatus;
+use jsonrpc::serde_json;
 
 mod testenv;
 
-use testenv::TestEnv;
+use testenv::{RpcProxy, TestEnv};
 
 #[test]
 fn test_invalid_credentials() {
@@ -55,6 +61,129 @@ fn test_client_with_custom_transport() {
         .expect("failed to call getbestblockhash");
 }
 
+#[test]
+fn test_client_builder() {
+    let env = TestEnv::setup().unwrap();
+
+    let client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .timeout(std::time::Duration::from_secs(5))
+        .user_agent("bdk-bitcoind-client-test")
+        .header("X-Test", "1")
+        .build()
+        .expect("failed to build client");
+
+    let best_hash = client.get_best_block_hash().unwrap();
+    assert_eq!(best_hash, env.client.get_best_block_hash().unwrap());
+}
+
+#[test]
+fn test_client_builder_max_response_size() {
+    let env = TestEnv::setup().unwrap();
+    let client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .max_response_size(64)
+        .build()
+        .unwrap();
+
+    // A block count response fits in 64 bytes, a raw genesis block does not.
+    client.get_block_count().unwrap();
+    let genesis_hash = client.get_block_hash(0).unwrap();
+    let err = client.get_block(&genesis_hash).unwrap_err();
+
+    assert!(err.is_transport_error());
+}
+
+#[test]
+fn test_client_builder_invalid_url() {
+    let result = ClientBuilder::new("127.0.0.1:18443").build();
+
+    assert!(matches!(result, Err(Error::InvalidUrl(_))));
+}
+
+#[test]
+fn test_retry_policy_gives_up_after_max_attempts() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+    let mut env = TestEnv::setup().unwrap();
+    let rpc_url = env.bitcoind.rpc_url();
+    let auth = Auth::CookieFile(env.bitcoind.params.cookie_file.clone());
+    let client = Client::with_auth(&rpc_url, auth)
+        .unwrap()
+        .with_retry_policy(
+            RetryPolicy::new()
+                .max_attempts(3)
+                .initial_backoff(Duration::from_millis(10))
+                .retry_if(|e| {
+                    ATTEMPTS.fetch_add(1, Ordering::SeqCst);
+                    RetryPolicy::is_transient(e)
+                }),
+        );
+    env.bitcoind.stop().unwrap();
+
+    let err = client.get_best_block_hash().unwrap_err();
+
+    assert!(err.is_transport_error());
+    // The classifier is consulted after each failed attempt but the last one.
+    assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_retry_policy_does_not_retry_invalid_credentials() {
+    let env = TestEnv::setup().unwrap();
+    let client = Client::with_auth(
+        &env.bitcoind.rpc_url(),
+        Auth::UserPass("wrong".to_string(), "credentials".to_string()),
+    )
+    .unwrap()
+    .with_retry_policy(RetryPolicy::new());
+
+    let err = client.get_best_block_hash().unwrap_err();
+
+    assert_eq!(err.http_status_code(), Some(401));
+}
+
+#[test]
+fn test_cookie_file_reread_after_restart() {
+    let mut env = TestEnv::setup().unwrap();
+    let proxy = RpcProxy::start(env.bitcoind.params.rpc_socket).unwrap();
+    let auth = Auth::CookieFile(env.bitcoind.params.cookie_file.clone());
+    let client = Client::with_auth(&proxy.url(), auth).unwrap();
+    let best_hash = client.get_best_block_hash().unwrap();
+
+    // The restarted node writes a new cookie, rejecting the credentials read at startup.
+    let _stopped = env.restart().unwrap();
+    proxy.set_target(env.bitcoind.params.rpc_socket);
+
+    assert_eq!(client.get_best_block_hash().unwrap(), best_hash);
+}
+
+#[test]
+fn test_server_version() {
+    let env = TestEnv::setup().unwrap();
+
+    let version = env
+        .client
+        .server_version()
+        .expect("failed to get server version");
+
+    // The tests may run against any supported version, see `contrib/test-version.sh`.
+    let info = env
+        .client
+        .call::<serde_json::Value>("getnetworkinfo", &[])
+        .unwrap();
+    let raw_version = info["version"].as_u64().expect("missing version") as usize;
+    assert_eq!(
+        version,
+        CoreVersion::from_server_version(raw_version).unwrap()
+    );
+    // The version is cached after the first call.
+    assert_eq!(env.client.server_version().unwrap(), version);
+}
+
 #[test]
 fn test_get_block_count() {
     let env = TestEnv::setup().unwrap();
@@ -154,6 +283,76 @@ fn test_get_block_after_mining() {
     assert!(!block.txdata.is_empty());
 }
 
+#[test]
+fn test_get_block_bytes() {
+    let env = TestEnv::setup().unwrap();
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+    let block = env
+        .client
+        .get_block(&block_hash)
+        .expect("failed to get block");
+
+    let bytes = env
+        .client
+        .get_block_bytes(&block_hash)
+        .expect("failed to get block bytes");
+
+    assert_eq!(bytes, serialize(&block));
+}
+
+#[test]
+fn test_get_block_bytes_over_rest() {
+    let env = TestEnv::setup().unwrap();
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+    let client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .rest(true)
+        .build()
+        .unwrap();
+
+    let bytes = client
+        .get_block_bytes(&block_hash)
+        .expect("failed to get block bytes");
+    let block = client.get_block(&block_hash).expect("failed to get block");
+
+    assert_eq!(
+        bytes,
+        env.client.get_block_bytes(&block_hash).unwrap(),
+        "REST and JSON-RPC must return the same block"
+    );
+    assert_eq!(block.block_hash(), block_hash);
+
+    // Unknown blocks fall back to JSON-RPC, which reports them as not found.
+    let unknown =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+            .unwrap();
+    assert!(
+        client
+            .get_block_bytes(&unknown)
+            .unwrap_err()
+            .is_not_found_error()
+    );
+}
+
+#[test]
+fn test_get_block_bytes_rest_disabled() {
+    let env = TestEnv::setup_with_args(&["-rest=0"]).unwrap();
+    let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+    let client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .rest(true)
+        .build()
+        .unwrap();
+
+    // The REST interface replies 404, the block is fetched over JSON-RPC instead.
+    for _ in 0..2 {
+        let bytes = client
+            .get_block_bytes(&block_hash)
+            .expect("failed to get block bytes");
+        assert_eq!(bytes, env.client.get_block_bytes(&block_hash).unwrap());
+    }
+}
+
 #[test]
 fn test_get_block_verbose() {
     let env = TestEnv::setup().unwrap();
@@ -170,6 +369,115 @@ fn test_get_block_verbose() {
     assert_eq!(get_block_verbose_one.confirmations, 1);
 }
 
+#[test]
+fn test_get_blockchain_info() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    let info = env
+        .client
+        .get_blockchain_info()
+        .expect("failed to get blockchain info");
+
+    assert_eq!(info.chain, Network::Regtest);
+    assert_eq!(info.blocks, 10);
+    assert_eq!(info.headers, 10);
+    assert_eq!(info.best_block_hash, hashes[9]);
+    assert!(!info.initial_block_download);
+    assert!(!info.pruned);
+    assert_eq!(info.prune_height, None);
+}
+
+#[test]
+fn test_get_chain_tips() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(3, None).expect("failed to mine blocks");
+
+    // Invalidating the tip and mining a competing block leaves a stale branch.
+    env.client
+        .call::<()>("invalidateblock", &[serde_json::json!(hashes[2])])
+        .unwrap();
+    let address = env.bitcoind.client.new_address().unwrap();
+    let fork = env
+        .mine_blocks(1, Some(address))
+        .expect("failed to mine block")[0];
+
+    let tips = env
+        .client
+        .get_chain_tips()
+        .expect("failed to get chain tips");
+
+    assert_eq!(tips.0.len(), 2);
+    let active = tips
+        .0
+        .iter()
+        .find(|tip| tip.status == ChainTipsStatus::Active)
+        .expect("missing active tip");
+    assert_eq!(
+        (active.hash, active.height, active.branch_length),
+        (fork, 3, 0)
+    );
+    let invalid = tips
+        .0
+        .iter()
+        .find(|tip| tip.status == ChainTipsStatus::Invalid)
+        .expect("missing invalid tip");
+    assert_eq!((invalid.hash, invalid.branch_length), (hashes[2], 1));
+}
+
+#[test]
+fn test_get_chain_tx_stats() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    let stats = env
+        .client
+        .get_chain_tx_stats(None, None)
+        .expect("failed to get chain tx stats");
+    assert_eq!(stats.window_final_block_hash, hashes[9]);
+    assert_eq!(stats.window_final_block_height, Some(10));
+    // Every block has a coinbase transaction.
+    assert_eq!(stats.tx_count, 11);
+
+    let stats = env
+        .client
+        .get_chain_tx_stats(Some(5), Some(&hashes[7]))
+        .expect("failed to get chain tx stats");
+    assert_eq!(stats.window_final_block_hash, hashes[7]);
+    assert_eq!(stats.window_block_count, 5);
+    assert_eq!(stats.window_tx_count, Some(5));
+}
+
+#[test]
+fn test_expected_network() {
+    let env = TestEnv::setup().unwrap();
+    let builder = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()));
+
+    let client = builder
+        .clone()
+        .expected_network(Network::Regtest)
+        .build()
+        .expect("node is on regtest");
+    client.check_network(Network::Regtest).unwrap();
+
+    let err = builder
+        .expected_network(Network::Bitcoin)
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::NetworkMismatch {
+            expected: Network::Bitcoin,
+            actual: Some(Network::Regtest),
+        }
+    ));
+    assert!(matches!(
+        env.client.check_network(Network::Signet),
+        Err(Error::NetworkMismatch { .. })
+    ));
+}
+
 #[test]
 fn test_get_block_invalid_hash() {
     let env = TestEnv::setup().unwrap();
@@ -280,6 +588,91 @@ fn test_get_raw_transaction() {
     assert_eq!(result_tx.compute_txid(), txid);
 }
 
+#[test]
+fn test_get_raw_transaction_info() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let block = env.client.get_block(&hashes[0]).unwrap();
+    let coinbase = &block.txdata[0];
+
+    let info = env
+        .client
+        .get_raw_transaction_info(&coinbase.compute_txid(), None)
+        .expect("failed to get raw transaction info");
+    assert_eq!(info.transaction, *coinbase);
+    assert_eq!(info.block_hash, Some(hashes[0]));
+    assert_eq!(info.confirmations, Some(101));
+    assert_eq!(info.block_time, Some(u64::from(block.header.time)));
+    assert_eq!(info.in_active_chain, None);
+
+    // Mempool transactions have no block.
+    let address = env.bitcoind.client.new_address().unwrap();
+    let txid = env
+        .bitcoind
+        .client
+        .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+        .unwrap()
+        .txid()
+        .unwrap();
+    let info = env
+        .client
+        .get_raw_transaction_info(&txid, None)
+        .expect("failed to get raw transaction info");
+    assert_eq!(info.transaction.compute_txid(), txid);
+    assert_eq!(info.block_hash, None);
+    assert_eq!(info.confirmations, None);
+}
+
+#[test]
+fn test_get_raw_transaction_info_without_txindex() {
+    // The pruned node runs without `-txindex`.
+    let env = TestEnv::setup_pruned().unwrap();
+    let hashes = env.mine_blocks(2, None).expect("failed to mine blocks");
+    let block = env.client.get_block(&hashes[0]).unwrap();
+    let txid = block.txdata[0].compute_txid();
+
+    let err = env
+        .client
+        .get_raw_transaction_info(&txid, None)
+        .unwrap_err();
+    assert!(err.is_not_found_error());
+
+    let info = env
+        .client
+        .get_raw_transaction_info(&txid, Some(&hashes[0]))
+        .expect("failed to get raw transaction info");
+    assert_eq!(info.transaction, block.txdata[0]);
+    assert_eq!(info.block_hash, Some(hashes[0]));
+    assert_eq!(info.confirmations, Some(2));
+    assert_eq!(info.in_active_chain, Some(true));
+
+    // The transaction is not in other blocks.
+    let err = env
+        .client
+        .get_raw_transaction_info(&txid, Some(&hashes[1]))
+        .unwrap_err();
+    assert!(err.is_not_found_error());
+}
+
+#[test]
+fn test_get_raw_transaction_info_stale_block() {
+    let env = TestEnv::setup().unwrap();
+    let hashes = env.mine_blocks(2, None).expect("failed to mine blocks");
+    let block = env.client.get_block(&hashes[1]).unwrap();
+    let txid = block.txdata[0].compute_txid();
+
+    env.invalidate_block(hashes[1])
+        .expect("failed to invalidate block");
+
+    let info = env
+        .client
+        .get_raw_transaction_info(&txid, Some(&hashes[1]))
+        .expect("failed to get raw transaction info");
+    assert_eq!(info.transaction, block.txdata[0]);
+    assert_eq!(info.block_hash, Some(hashes[1]));
+    assert_eq!(info.in_active_chain, Some(false));
+}
+
 #[test]
 fn test_get_raw_transaction_invalid_txid() {
     let env = TestEnv::setup().unwrap();
@@ -309,3 +702,703 @@ fn test_get_block_filter() {
 
     assert!(!result.filter.is_empty());
 }
+
+#[test]
+fn test_batch_get_block_hashes_and_headers() {
+    let env = TestEnv::setup().unwrap();
+
+    let mined = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    let mut batch = env.client.batch();
+    let calls = (1..=10)
+        .map(|height| batch.get_block_hash(height))
+        .collect::<Vec<_>>();
+    let response = batch.send().expect("failed to send batch");
+
+    let hashes = calls
+        .iter()
+        .map(|call| response.get(call))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to get block hashes");
+    assert_eq!(hashes, mined);
+
+    let mut batch = env.client.batch();
+    let calls = hashes
+        .iter()
+        .map(|hash| batch.get_block_header(hash))
+        .collect::<Vec<_>>();
+    let response = batch.send().expect("failed to send batch");
+
+    for (call, hash) in calls.iter().zip(&hashes) {
+        let header = response.get(call).expect("failed to get block header");
+        assert_eq!(header.block_hash(), *hash);
+    }
+}
+
+#[test]
+fn test_batch_error_does_not_poison_other_calls() {
+    let env = TestEnv::setup().unwrap();
+
+    let mut batch = env.client.batch();
+    let genesis = batch.get_block_hash(0);
+    let missing = batch.get_block_hash(999_999_999);
+    let count = batch.get_block_count();
+    let response = batch.send().expect("failed to send batch");
+
+    assert_eq!(response.len(), 3);
+    assert!(response.get(&genesis).is_ok());
+    assert!(response.get(&missing).is_err());
+    assert_eq!(response.get(&count).expect("failed to get block count"), 0);
+}
+
+#[test]
+fn test_batch_empty() {
+    let env = TestEnv::setup().unwrap();
+
+    let response = env.client.batch().send().expect("failed to send batch");
+
+    assert!(response.is_empty());
+}
+
+#[test]
+fn test_test_mempool_accept_and_send_raw_transaction() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let address = env.bitcoind.client.new_address().unwrap();
+    let tx = env
+        .create_signed_tx(&address, Amount::from_sat(100_000))
+        .expect("failed to create transaction");
+    let txid = tx.compute_txid();
+
+    let results = env
+        .client
+        .test_mempool_accept(core::slice::from_ref(&tx))
+        .expect("failed to test mempool accept");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].txid, txid);
+    assert!(results[0].allowed);
+    assert!(results[0].vsize.is_some());
+    assert!(results[0].fees.is_some());
+
+    let sent = env
+        .client
+        .send_raw_transaction(&tx)
+        .expect("failed to send transaction");
+    assert_eq!(sent, txid);
+    assert!(env.client.get_raw_mempool().unwrap().contains(&txid));
+
+    // Once in the mempool, the transaction is no longer accepted.
+    let results = env.client.test_mempool_accept(&[tx]).unwrap();
+    assert!(!results[0].allowed);
+    assert_eq!(
+        RejectReason::from_message(results[0].reject_reason.as_deref().unwrap()),
+        RejectReason::AlreadyInMempool
+    );
+}
+
+#[test]
+fn test_send_raw_transaction_max_fee_rate_exceeded() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let address = env.bitcoind.client.new_address().unwrap();
+    let tx = env
+        .create_signed_tx(&address, Amount::from_sat(100_000))
+        .unwrap();
+
+    let options = BroadcastOptions {
+        max_fee_rate: Some(FeeRate::from_sat_per_vb_unchecked(1)),
+        ..Default::default()
+    };
+    let err = env
+        .client
+        .send_raw_transaction_with_options(&tx, options)
+        .unwrap_err();
+
+    let Error::TxRejected(rejection) = err else {
+        panic!("expected a rejected transaction, got {err:?}");
+    };
+    assert_eq!(rejection.txid, tx.compute_txid());
+    assert_eq!(rejection.reason, RejectReason::MaxFeeExceeded);
+}
+
+#[test]
+fn test_send_raw_transaction_already_in_chain() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let address = env.bitcoind.client.new_address().unwrap();
+    let tx = env
+        .create_signed_tx(&address, Amount::from_sat(100_000))
+        .unwrap();
+    env.client.send_raw_transaction(&tx).unwrap();
+    let _hashes = env.mine_blocks(1, None).expect("failed to mine block");
+
+    let err = env.client.send_raw_transaction(&tx).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::TxRejected(TxRejection {
+            reason: RejectReason::AlreadyInChain,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_submit_package() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let address = env.bitcoind.client.new_address().unwrap();
+    let parent = env
+        .create_signed_tx(&address, Amount::from_sat(100_000))
+        .expect("failed to create parent");
+    let child = env
+        .create_signed_child_tx(&parent, Amount::from_sat(10_000))
+        .expect("failed to create child");
+
+    let result = env
+        .client
+        .submit_package(
+            &[parent.clone(), child.clone()],
+            BroadcastOptions::default(),
+        )
+        .expect("failed to submit package");
+
+    assert_eq!(result.package_msg, "success");
+    assert_eq!(result.tx_results.len(), 2);
+    assert!(result.replaced_transactions.is_empty());
+    let child_result = &result.tx_results[&child.compute_wtxid()];
+    assert_eq!(child_result.txid, child.compute_txid());
+    assert!(child_result.error.is_none());
+    assert!(child_result.fees.is_some());
+
+    let mempool = env.client.get_raw_mempool().unwrap();
+    assert!(mempool.contains(&parent.compute_txid()));
+    assert!(mempool.contains(&child.compute_txid()));
+}
+
+#[test]
+fn test_estimate_smart_fee_unavailable() {
+    let env = TestEnv::setup().unwrap();
+    let _hashes = env.mine_blocks(10, None).expect("failed to mine blocks");
+
+    // A fresh regtest node has not seen enough transactions confirm to estimate fees.
+    let err = env
+        .client
+        .estimate_smart_fee(6, EstimateMode::Conservative)
+        .unwrap_err();
+    let Error::FeeEstimateUnavailable(errors) = err else {
+        panic!("expected no fee estimate, got {err:?}");
+    };
+    assert!(!errors.is_empty());
+
+    let table = env
+        .client
+        .estimate_fee_table(&FEE_TABLE_TARGETS, EstimateMode::Economical)
+        .expect("failed to estimate fee table");
+    assert!(table.is_empty());
+}
+
+#[test]
+fn test_estimate_smart_fee() {
+    let env = TestEnv::setup().unwrap();
+    env.populate_fee_estimator(25)
+        .expect("failed to populate fee estimator");
+
+    let fee_rate = env
+        .client
+        .estimate_smart_fee(6, EstimateMode::Economical)
+        .expect("failed to estimate fee");
+
+    // `estimatesmartfee` reports BTC/kvB, the client converts it to sat/vB.
+    let raw: serde_json::Value = env
+        .client
+        .call(
+            "estimatesmartfee",
+            &[serde_json::json!(6), serde_json::json!("economical")],
+        )
+        .unwrap();
+    let sat_per_kvb = Amount::from_btc(raw["feerate"].as_f64().unwrap())
+        .unwrap()
+        .to_sat();
+    assert_eq!(fee_rate.to_sat_per_kwu(), sat_per_kvb / 4);
+    assert_eq!(fee_rate.to_sat_per_vb_floor(), sat_per_kvb / 1000);
+    assert!(fee_rate >= FeeRate::from_sat_per_vb_unchecked(10));
+
+    let table = env
+        .client
+        .estimate_fee_table(&FEE_TABLE_TARGETS, EstimateMode::Economical)
+        .expect("failed to estimate fee table");
+    assert_eq!(table.keys().copied().collect::<Vec<_>>(), FEE_TABLE_TARGETS);
+    assert_eq!(table[&6], fee_rate);
+}
+
+#[test]
+fn test_wait_for_new_block() {
+    let env = TestEnv::setup().unwrap();
+
+    let hash = std::thread::scope(|s| {
+        let waiter = s.spawn(|| env.client.wait_for_new_block(Duration::from_secs(30)));
+        std::thread::sleep(Duration::from_millis(500));
+        let hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+        assert_eq!(waiter.join().unwrap().expect("failed to wait"), (hash, 1));
+        hash
+    });
+
+    // `waitforblock` returns immediately once the block is connected.
+    let tip = env
+        .client
+        .wait_for_block(&hash, Duration::from_secs(30))
+        .expect("failed to wait for block");
+    assert_eq!(tip, (hash, 1));
+}
+
+#[test]
+fn test_wait_for_block_height() {
+    let env = TestEnv::setup().unwrap();
+
+    let tip = std::thread::scope(|s| {
+        let waiter = s.spawn(|| env.client.wait_for_block_height(5, Duration::from_secs(30)));
+        let hashes = env.mine_blocks(5, None).expect("failed to mine blocks");
+        assert_eq!(
+            waiter.join().unwrap().expect("failed to wait"),
+            (hashes[4], 5)
+        );
+        hashes[4]
+    });
+
+    // The current tip is returned if the height is not reached before the timeout.
+    let start = Instant::now();
+    let result = env
+        .client
+        .wait_for_block_height(10, Duration::from_millis(200))
+        .expect("failed to wait for block height");
+    assert_eq!(result, (tip, 5));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn test_wait_for_new_block_outlives_request_timeout() {
+    let env = TestEnv::setup().unwrap();
+    let client = ClientBuilder::new(&env.bitcoind.rpc_url())
+        .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()))
+        .timeout(Duration::from_secs(1))
+        .build()
+        .unwrap();
+    let tip = env.client.get_best_block_hash().unwrap();
+
+    // The long poll outlasts the 1 second request timeout of the client.
+    let result = client
+        .wait_for_new_block(Duration::from_secs(3))
+        .expect("long poll must not time out");
+
+    assert_eq!(result, (tip, 0));
+}
+
+#[cfg(feature = "async")]
+mod async_client {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_invalid_credentials() {
+        let env = TestEnv::setup().unwrap();
+        let client = bdk_bitcoind_client::AsyncClient::with_auth(
+            &env.bitcoind.rpc_url(),
+            Auth::UserPass("wrong".to_string(), "credentials".to_string()),
+        )
+        .expect("client creation should succeed");
+
+        let result: Result<BlockHash, Error> = client.get_best_block_hash().await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_builder() {
+        let env = TestEnv::setup().unwrap();
+        let builder = ClientBuilder::new(&env.bitcoind.rpc_url())
+            .auth(Auth::CookieFile(env.bitcoind.params.cookie_file.clone()));
+
+        let client = builder
+            .clone()
+            .timeout(Duration::from_secs(5))
+            .user_agent("bdk-bitcoind-client-test")
+            .header("X-Test", "1")
+            .max_response_size(64)
+            .expected_network(Network::Regtest)
+            .build_async()
+            .await
+            .expect("failed to build client");
+
+        // A block count response fits in 64 bytes, a raw genesis block does not.
+        assert_eq!(client.get_block_count().await.unwrap(), 0);
+        let genesis_hash = client.get_block_hash(0).await.unwrap();
+        let err = client.get_block(&genesis_hash).await.unwrap_err();
+        assert!(err.is_transport_error());
+
+        let err = builder
+            .expected_network(Network::Bitcoin)
+            .build_async()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NetworkMismatch { .. }));
+        assert!(matches!(
+            ClientBuilder::new("127.0.0.1:18443").build_async().await,
+            Err(Error::InvalidUrl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_gives_up_after_max_attempts() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap().with_retry_policy(
+            RetryPolicy::new()
+                .max_attempts(3)
+                .initial_backoff(Duration::from_millis(10))
+                .retry_if(|e| {
+                    ATTEMPTS.fetch_add(1, Ordering::SeqCst);
+                    RetryPolicy::is_transient(e)
+                }),
+        );
+        env.bitcoind.stop().unwrap();
+
+        let err = client.get_best_block_hash().await.unwrap_err();
+
+        assert!(err.is_connection_error());
+        assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_handles_target_their_wallet() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+        client.create_wallet("alice").await.unwrap();
+        client.create_wallet("bob").await.unwrap();
+
+        let alice = client.wallet("alice").unwrap();
+        let bob = client.wallet("bob").unwrap();
+        assert_eq!(alice.wallet_name(), Some("alice"));
+        assert_eq!(client.wallet_name(), None);
+
+        let address: String = alice.call("getnewaddress", &[]).await.unwrap();
+        let address = address
+            .parse::<corepc_types::bitcoin::Address<_>>()
+            .unwrap()
+            .assume_checked();
+        env.mine_blocks(101, Some(address)).unwrap();
+
+        let alice_balance: f64 = alice.call("getbalance", &[]).await.unwrap();
+        let bob_balance: f64 = bob.call("getbalance", &[]).await.unwrap();
+        assert_eq!(
+            Amount::from_btc(alice_balance).unwrap(),
+            Amount::from_int_btc(50)
+        );
+        assert_eq!(bob_balance, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_get_block_count() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let block_count = client
+            .get_block_count()
+            .await
+            .expect("failed to get block count");
+
+        assert_eq!(block_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_best_block_hash() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let best_block_hash = client
+            .get_best_block_hash()
+            .await
+            .expect("failed to get best block hash");
+
+        let block_count = client
+            .get_block_count()
+            .await
+            .expect("failed to get block count");
+        let block_hash = client
+            .get_block_hash(block_count)
+            .await
+            .expect("failed to get block hash");
+
+        assert_eq!(best_block_hash, block_hash);
+    }
+
+    #[tokio::test]
+    async fn test_get_block_hash_invalid_height() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let result = client.get_block_hash(999_999_999).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_block() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let hashes = env.mine_blocks(1, None).expect("failed to mine block");
+        let block_hash = hashes[0];
+
+        let block = client
+            .get_block(&block_hash)
+            .await
+            .expect("failed to get block");
+
+        assert_eq!(block.block_hash(), block_hash);
+        assert!(!block.txdata.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_block_bytes() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+        let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+        let bytes = client
+            .get_block_bytes(&block_hash)
+            .await
+            .expect("failed to get block bytes");
+
+        assert_eq!(bytes, env.client.get_block_bytes(&block_hash).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_block_bytes_over_rest() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap().with_rest(true);
+        let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+        let bytes = client
+            .get_block_bytes(&block_hash)
+            .await
+            .expect("failed to get block bytes");
+        assert_eq!(bytes, env.client.get_block_bytes(&block_hash).unwrap());
+
+        // Unknown blocks fall back to JSON-RPC, which reports them as not found.
+        let unknown =
+            BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+        let err = client.get_block_bytes(&unknown).await.unwrap_err();
+        assert!(err.is_not_found_error());
+    }
+
+    #[tokio::test]
+    async fn test_get_block_bytes_rest_disabled() {
+        let env = TestEnv::setup_with_args(&["-rest=0"]).unwrap();
+        let client = env.async_client().unwrap().with_rest(true);
+        let block_hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+        let block = client
+            .get_block(&block_hash)
+            .await
+            .expect("failed to get block");
+        assert_eq!(block.block_hash(), block_hash);
+    }
+
+    #[tokio::test]
+    async fn test_get_block_verbose() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let hashes = env.mine_blocks(1, None).expect("failed to mine block");
+        let block_hash = hashes[0];
+
+        let get_block_verbose_one = client
+            .get_block_verbose(&block_hash)
+            .await
+            .expect("failed to get block verbose 1");
+
+        assert_eq!(get_block_verbose_one.hash, block_hash);
+        assert_eq!(get_block_verbose_one.confirmations, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_blockchain_info() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+        let hashes = env.mine_blocks(2, None).expect("failed to mine blocks");
+
+        let info = client
+            .get_blockchain_info()
+            .await
+            .expect("failed to get blockchain info");
+        assert_eq!(info.chain, Network::Regtest);
+        assert_eq!(info.best_block_hash, hashes[1]);
+
+        let tips = client
+            .get_chain_tips()
+            .await
+            .expect("failed to get chain tips");
+        assert_eq!(tips.0.len(), 1);
+        assert_eq!(tips.0[0].hash, hashes[1]);
+
+        let stats = client
+            .get_chain_tx_stats(Some(1), None)
+            .await
+            .expect("failed to get chain tx stats");
+        assert_eq!(stats.window_final_block_hash, hashes[1]);
+    }
+
+    #[tokio::test]
+    async fn test_check_network() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        client.check_network(Network::Regtest).await.unwrap();
+        assert!(matches!(
+            client.check_network(Network::Testnet).await,
+            Err(Error::NetworkMismatch {
+                expected: Network::Testnet,
+                actual: Some(Network::Regtest),
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_header() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let genesis_hash = client
+            .get_block_hash(0)
+            .await
+            .expect("failed to get genesis hash");
+
+        let header = client
+            .get_block_header(&genesis_hash)
+            .await
+            .expect("failed to get block header");
+        let header_verbose = client
+            .get_block_header_verbose(&genesis_hash)
+            .await
+            .expect("failed to get block header verbose");
+
+        assert_eq!(header.block_hash(), genesis_hash);
+        assert_eq!(header_verbose.hash, genesis_hash);
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_mempool_with_transaction() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let _hashes = env.mine_blocks(101, None).expect("failed to mine block");
+
+        let address = env.bitcoind.client.new_address().unwrap();
+        let txid = env
+            .bitcoind
+            .client
+            .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+            .expect("failed to send to address")
+            .into_model()
+            .unwrap()
+            .txid;
+
+        let mempool = client
+            .get_raw_mempool()
+            .await
+            .expect("failed to get mempool");
+        assert!(mempool.contains(&txid));
+
+        let tx = client
+            .get_raw_transaction(&txid)
+            .await
+            .expect("failed to get raw transaction");
+        assert_eq!(tx.compute_txid(), txid);
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_transaction_info() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+        let hashes = env.mine_blocks(1, None).expect("failed to mine block");
+        let block = client.get_block(&hashes[0]).await.unwrap();
+        let txid = block.txdata[0].compute_txid();
+
+        let info = client
+            .get_raw_transaction_info(&txid, Some(&hashes[0]))
+            .await
+            .expect("failed to get raw transaction info");
+
+        assert_eq!(info.transaction.compute_txid(), txid);
+        assert_eq!(info.block_hash, Some(hashes[0]));
+        assert_eq!(info.confirmations, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_filter() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        let genesis_hash = client
+            .get_block_hash(0)
+            .await
+            .expect("failed to get genesis hash");
+
+        let result = client
+            .get_block_filter(&genesis_hash)
+            .await
+            .expect("failed to get block filter");
+
+        assert!(!result.filter.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_estimate_fee_table() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+
+        // A fresh regtest node has not seen enough transactions confirm to estimate fees.
+        let table = client
+            .estimate_fee_table(&FEE_TABLE_TARGETS, EstimateMode::Economical)
+            .await
+            .expect("failed to estimate fee table");
+        assert!(table.is_empty());
+
+        env.populate_fee_estimator(25)
+            .expect("failed to populate fee estimator");
+        let table = client
+            .estimate_fee_table(&FEE_TABLE_TARGETS, EstimateMode::Economical)
+            .await
+            .expect("failed to estimate fee table");
+        assert_eq!(
+            table,
+            env.client
+                .estimate_fee_table(&FEE_TABLE_TARGETS, EstimateMode::Economical)
+                .unwrap()
+        );
+        assert_eq!(table.len(), FEE_TABLE_TARGETS.len());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_block_height() {
+        let env = TestEnv::setup().unwrap();
+        let client = env.async_client().unwrap();
+        let hashes = env.mine_blocks(2, None).expect("failed to mine blocks");
+
+        let tip = client
+            .wait_for_block_height(1, Duration::from_secs(30))
+            .await
+            .expect("failed to wait for block height");
+        assert_eq!(tip, (hashes[1], 2));
+
+        let tip = client
+            .wait_for_new_block(Duration::from_millis(200))
+            .await
+            .expect("failed to wait for new block");
+        assert_eq!(tip, (hashes[1], 2));
+    }
+}
@@ -1,9 +1,19 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+// Not every test crate uses every helper.
+#![allow(dead_code)]
+
+#[cfg(feature = "async")]
+use bdk_bitcoind_client::AsyncClient;
 use bdk_bitcoind_client::{Auth, Client};
-use bitcoin::{Address, BlockHash};
+use bitcoin::{Address, Amount, BlockHash, Transaction, consensus::encode::deserialize_hex};
 use bitcoind::{BitcoinD, Conf, exe_path};
 use corepc_types::bitcoin;
+use jsonrpc::serde_json;
+use std::io;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 /// Test environment for running integration tests.
 ///
@@ -27,13 +37,30 @@ impl TestEnv {
     /// creating different test cases, but be aware that this is different from the client we're
     /// actually testing.
     pub fn setup() -> anyhow::Result<Self> {
-        let exe = exe_path()?;
+        Self::setup_with_args(&[])
+    }
 
-        let mut conf = Conf::default();
-        conf.args.push("-blockfilterindex=1");
-        conf.args.push("-txindex=1");
+    /// Create new [`TestEnv`], passing the additional `args` to `bitcoind`.
+    pub fn setup_with_args(args: &[&str]) -> anyhow::Result<Self> {
+        let mut conf = Self::conf();
+        conf.args.extend(args);
+        Self::setup_with_conf(&conf)
+    }
 
-        let bitcoind = BitcoinD::with_conf(exe, &conf)?;
+    /// Create new [`TestEnv`] with a node pruned manually with `pruneblockchain`.
+    ///
+    /// The node writes small block files (`-fastprune`), so a few hundred blocks can be pruned.
+    /// Pruning is incompatible with `-txindex`, which is disabled.
+    pub fn setup_pruned() -> anyhow::Result<Self> {
+        let mut conf = Self::conf();
+        conf.args.retain(|arg| *arg != "-txindex=1");
+        conf.args.extend(["-prune=1", "-fastprune"]);
+        Self::setup_with_conf(&conf)
+    }
+
+    /// Create new [`TestEnv`] running `bitcoind` with `conf`.
+    fn setup_with_conf(conf: &Conf) -> anyhow::Result<Self> {
+        let bitcoind = BitcoinD::with_conf(exe_path()?, conf)?;
 
         let rpc_url = bitcoind.rpc_url();
         let cookie_file = &bitcoind.params.cookie_file;
@@ -43,6 +70,41 @@ impl TestEnv {
         Ok(Self { client, bitcoind })
     }
 
+    /// The [`Conf`] used to start [`bitcoind::BitcoinD`].
+    fn conf() -> Conf<'static> {
+        let mut conf = Conf::default();
+        conf.args.push("-blockfilterindex=1");
+        conf.args.push("-txindex=1");
+        conf.args.push("-rest=1");
+        conf
+    }
+
+    /// Stops the node and starts it again on the same data directory.
+    ///
+    /// The restarted node listens on a different RPC port, and writes a fresh cookie file to the
+    /// same path. Use an [`RpcProxy`] to keep a stable URL across restarts.
+    ///
+    /// Returns the stopped node, which owns the data directory and removes it when dropped.
+    pub fn restart(&mut self) -> anyhow::Result<BitcoinD> {
+        let workdir = self.bitcoind.workdir();
+        self.bitcoind.stop()?;
+
+        let mut conf = Self::conf();
+        conf.staticdir = Some(workdir);
+        // The wallet created by the first node is still in the data directory.
+        conf.wallet = None;
+        let bitcoind = BitcoinD::with_conf(exe_path()?, &conf)?;
+
+        Ok(std::mem::replace(&mut self.bitcoind, bitcoind))
+    }
+
+    /// Creates an [`AsyncClient`] connected to the same [`bitcoind::BitcoinD`] instance.
+    #[cfg(feature = "async")]
+    pub fn async_client(&self) -> anyhow::Result<AsyncClient> {
+        let auth = Auth::CookieFile(self.bitcoind.params.cookie_file.clone());
+        Ok(AsyncClient::with_auth(&self.bitcoind.rpc_url(), auth)?)
+    }
+
     /// Mines `nblocks` blocks to the given `address`, or an address controlled
     /// by the [`Node`] if not provided.
     pub fn mine_blocks(
@@ -61,4 +123,150 @@ impl TestEnv {
             .into_model()?
             .0)
     }
+
+    /// Creates a transaction paying `amount` to `address`, funded and signed by the wallet of the
+    /// [`Node`] but not broadcast.
+    pub fn create_signed_tx(
+        &self,
+        address: &Address,
+        amount: Amount,
+    ) -> anyhow::Result<Transaction> {
+        let client = &self.bitcoind.client;
+        let outputs = serde_json::json!({ address.to_string(): amount.to_btc() });
+        let raw: String = client.call("createrawtransaction", &[serde_json::json!([]), outputs])?;
+        let funded: serde_json::Value =
+            client.call("fundrawtransaction", &[serde_json::json!(raw)])?;
+        let signed: serde_json::Value =
+            client.call("signrawtransactionwithwallet", &[funded["hex"].clone()])?;
+        let hex = signed["hex"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("missing signed transaction"))?;
+        Ok(deserialize_hex(hex)?)
+    }
+
+    /// Creates a transaction spending the output of `parent` paying to an address of the wallet of
+    /// the [`Node`], paying `fee` to miners. The transaction is signed but not broadcast.
+    pub fn create_signed_child_tx(
+        &self,
+        parent: &Transaction,
+        fee: Amount,
+    ) -> anyhow::Result<Transaction> {
+        let client = &self.bitcoind.client;
+        let (vout, prevout) = parent
+            .output
+            .iter()
+            .enumerate()
+            .find(|(_, txout)| txout.value > fee)
+            .ok_or_else(|| anyhow::anyhow!("parent has no output large enough"))?;
+        let address = client.new_address()?;
+        let inputs = serde_json::json!([{ "txid": parent.compute_txid(), "vout": vout }]);
+        let outputs = serde_json::json!({ address.to_string(): (prevout.value - fee).to_btc() });
+        let raw: String = client.call("createrawtransaction", &[inputs, outputs])?;
+        let prevtxs = serde_json::json!([{
+            "txid": parent.compute_txid(),
+            "vout": vout,
+            "scriptPubKey": prevout.script_pubkey.to_hex_string(),
+            "amount": prevout.value.to_btc(),
+        }]);
+        let signed: serde_json::Value = client.call(
+            "signrawtransactionwithwallet",
+            &[serde_json::json!(raw), prevtxs],
+        )?;
+        let hex = signed["hex"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("missing signed transaction"))?;
+        Ok(deserialize_hex(hex)?)
+    }
+
+    /// Feeds the fee estimator of the [`Node`] with `rounds` blocks, each confirming ten wallet
+    /// transactions paying between 10 and 19 sat/vB.
+    ///
+    /// The estimator only tracks transactions it saw in the mempool without unconfirmed parents,
+    /// so enough blocks are mined first for each transaction to spend a confirmed coinbase.
+    pub fn populate_fee_estimator(&self, rounds: usize) -> anyhow::Result<()> {
+        let client = &self.bitcoind.client;
+        self.mine_blocks(100 + rounds * 10, None)?;
+        for _ in 0..rounds {
+            for fee_rate in 10..20 {
+                let address = client.new_address()?;
+                let args = [
+                    serde_json::json!(address.to_string()),
+                    serde_json::json!(0.001),
+                    serde_json::Value::Null,
+                    serde_json::Value::Null,
+                    serde_json::json!(false),
+                    serde_json::json!(true),
+                    serde_json::Value::Null,
+                    serde_json::json!("unset"),
+                    serde_json::Value::Null,
+                    serde_json::json!(fee_rate),
+                ];
+                client.call::<serde_json::Value>("sendtoaddress", &args)?;
+            }
+            self.mine_blocks(1, None)?;
+        }
+        Ok(())
+    }
+
+    /// Marks the block with `hash` as invalid on the [`Node`], reorging it out of the best chain.
+    pub fn invalidate_block(&self, hash: BlockHash) -> anyhow::Result<()> {
+        self.bitcoind
+            .client
+            .call::<serde_json::Value>("invalidateblock", &[serde_json::json!(hash)])?;
+        Ok(())
+    }
+}
+
+/// A TCP proxy forwarding connections to the RPC port of a node, which can be changed while
+/// clients keep connecting to the same address.
+#[derive(Debug)]
+pub struct RpcProxy {
+    /// The address the proxy listens on.
+    addr: SocketAddr,
+    /// The address connections are forwarded to.
+    target: Arc<Mutex<SocketAddr>>,
+}
+
+impl RpcProxy {
+    /// Starts a proxy forwarding connections to `target`.
+    pub fn start(target: impl Into<SocketAddr>) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let target = Arc::new(Mutex::new(target.into()));
+
+        let current_target = Arc::clone(&target);
+        thread::spawn(move || {
+            for conn in listener.incoming() {
+                let Ok(client) = conn else { continue };
+                let target = *current_target.lock().unwrap();
+                let Ok(server) = TcpStream::connect(target) else {
+                    continue;
+                };
+                let (Ok(client_read), Ok(server_read)) = (client.try_clone(), server.try_clone())
+                else {
+                    continue;
+                };
+                thread::spawn(move || forward(client_read, server));
+                thread::spawn(move || forward(server_read, client));
+            }
+        });
+
+        Ok(Self { addr, target })
+    }
+
+    /// Returns the URL clients should connect to.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Forwards new connections to `target`.
+    pub fn set_target(&self, target: impl Into<SocketAddr>) {
+        *self.target.lock().unwrap() = target.into();
+    }
+}
+
+/// Copies bytes from `from` to `to` until `from` is closed.
+fn forward(mut from: TcpStream, mut to: TcpStream) {
+    let _ = io::copy(&mut from, &mut to);
+    let _ = to.shutdown(Shutdown::Write);
 }
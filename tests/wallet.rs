@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for the multi-wallet support of [`Client`](bdk_bitcoind_client::Client).
+
+use bdk_bitcoind_client::Error;
+use corepc_types::bitcoin::{Address, Amount};
+
+mod testenv;
+
+use testenv::TestEnv;
+
+#[test]
+fn test_create_list_unload_load_wallet() {
+    let env = TestEnv::setup().unwrap();
+
+    let created = env.client.create_wallet("alice").unwrap();
+    assert_eq!(created.name, "alice");
+    assert!(
+        env.client
+            .list_wallets()
+            .unwrap()
+            .contains(&"alice".to_string())
+    );
+
+    env.client.unload_wallet("alice").unwrap();
+    assert!(
+        !env.client
+            .list_wallets()
+            .unwrap()
+            .contains(&"alice".to_string())
+    );
+
+    let loaded = env.client.load_wallet("alice").unwrap();
+    assert_eq!(loaded.name, "alice");
+    assert!(
+        env.client
+            .list_wallets()
+            .unwrap()
+            .contains(&"alice".to_string())
+    );
+}
+
+#[test]
+fn test_wallet_handles_target_their_wallet() {
+    let env = TestEnv::setup().unwrap();
+    env.client.create_wallet("alice").unwrap();
+    env.client.create_wallet("bob").unwrap();
+
+    let alice = env.client.wallet("alice").unwrap();
+    let bob = env.client.wallet("bob").unwrap();
+    assert_eq!(alice.wallet_name(), Some("alice"));
+    assert_eq!(env.client.wallet_name(), None);
+
+    let address: String = alice.call("getnewaddress", &[]).unwrap();
+    let address = address.parse::<Address<_>>().unwrap().assume_checked();
+    env.mine_blocks(101, Some(address)).unwrap();
+
+    let alice_balance: f64 = alice.call("getbalance", &[]).unwrap();
+    let bob_balance: f64 = bob.call("getbalance", &[]).unwrap();
+    assert_eq!(
+        Amount::from_btc(alice_balance).unwrap(),
+        Amount::from_int_btc(50)
+    );
+    assert_eq!(bob_balance, 0.0);
+
+    // Non-wallet RPCs keep working through a wallet handle.
+    assert_eq!(
+        bob.get_best_block_hash().unwrap(),
+        env.client.get_best_block_hash().unwrap()
+    );
+}
+
+#[test]
+fn test_wallet_handle_to_unloaded_wallet() {
+    let env = TestEnv::setup().unwrap();
+
+    let carol = env.client.wallet("carol").unwrap();
+    let err = carol.call::<f64>("getbalance", &[]).unwrap_err();
+
+    assert!(err.is_wallet_not_found());
+}
+
+#[test]
+fn test_wallet_handle_requires_builder() {
+    let transport = jsonrpc::bitreq_http::Builder::new()
+        .url("http://127.0.0.1:18443")
+        .unwrap()
+        .build();
+    let client = bdk_bitcoind_client::Client::with_transport(transport);
+
+    assert!(matches!(
+        client.wallet("alice"),
+        Err(Error::CustomTransport)
+    ));
+}
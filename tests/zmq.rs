@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Integration tests for the `bdk_bitcoind_client` [`ZmqSubscriber`].
+
+#![cfg(feature = "zmq")]
+
+use std::net::TcpListener;
+use std::time::Duration;
+
+use bdk_bitcoind_client::{Error, SequenceEvent, ZmqEvent, ZmqSubscriber, ZmqTopic};
+use corepc_types::bitcoin::Amount;
+
+mod testenv;
+
+use testenv::TestEnv;
+
+/// How long to wait for a notification.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns a local ZMQ endpoint on a free port.
+fn endpoint() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("tcp://{}", listener.local_addr().unwrap())
+}
+
+/// Starts a node publishing the `hashblock`, `rawtx` and `sequence` notifications, and returns a
+/// subscriber discovered from `getzmqnotifications`.
+fn setup() -> (TestEnv, ZmqSubscriber) {
+    let endpoint = endpoint();
+    let args = [
+        format!("-zmqpubhashblock={endpoint}"),
+        format!("-zmqpubrawtx={endpoint}"),
+        format!("-zmqpubsequence={endpoint}"),
+    ];
+    let env =
+        TestEnv::setup_with_args(&args.iter().map(String::as_str).collect::<Vec<_>>()).unwrap();
+    let subscriber = ZmqSubscriber::discover(&env.client).expect("failed to subscribe");
+
+    // Subscriptions are asynchronous, wait for them before publishing.
+    std::thread::sleep(Duration::from_millis(500));
+
+    (env, subscriber)
+}
+
+/// Receives notifications until one matches `f`.
+fn recv_until(subscriber: &mut ZmqSubscriber, f: impl Fn(&ZmqEvent) -> bool) -> ZmqEvent {
+    loop {
+        let message = subscriber
+            .recv_timeout(TIMEOUT)
+            .expect("failed to receive")
+            .expect("timed out waiting for notification");
+        assert_eq!(message.missed, 0);
+        if f(&message.event) {
+            return message.event;
+        }
+    }
+}
+
+#[test]
+fn test_get_zmq_notifications() {
+    let (env, _subscriber) = setup();
+
+    let notifications = env.client.get_zmq_notifications().unwrap();
+
+    let mut types = notifications
+        .iter()
+        .map(|n| n.type_.as_str())
+        .collect::<Vec<_>>();
+    types.sort();
+    assert_eq!(types, vec!["pubhashblock", "pubrawtx", "pubsequence"]);
+    assert!(
+        notifications
+            .iter()
+            .all(|n| ZmqTopic::from_notification_type(&n.type_).is_some())
+    );
+}
+
+#[test]
+fn test_zmq_block_notifications() {
+    let (env, mut subscriber) = setup();
+
+    let hash = env.mine_blocks(1, None).expect("failed to mine block")[0];
+
+    let event = recv_until(&mut subscriber, |e| matches!(e, ZmqEvent::HashBlock(_)));
+    assert_eq!(event, ZmqEvent::HashBlock(hash));
+    let event = recv_until(&mut subscriber, |e| {
+        matches!(e, ZmqEvent::Sequence(SequenceEvent::BlockConnected(_)))
+    });
+    assert_eq!(
+        event,
+        ZmqEvent::Sequence(SequenceEvent::BlockConnected(hash))
+    );
+}
+
+#[test]
+fn test_zmq_transaction_notifications() {
+    let (env, mut subscriber) = setup();
+    let _hashes = env.mine_blocks(101, None).expect("failed to mine blocks");
+    let address = env.bitcoind.client.new_address().unwrap();
+
+    let txid = env
+        .bitcoind
+        .client
+        .send_to_address(&address, Amount::from_btc(0.001).unwrap())
+        .unwrap()
+        .txid()
+        .unwrap();
+
+    let event = recv_until(
+        &mut subscriber,
+        |e| matches!(e, ZmqEvent::RawTx(tx) if tx.compute_txid() == txid),
+    );
+    assert!(matches!(event, ZmqEvent::RawTx(_)));
+    let event = recv_until(&mut subscriber, |e| {
+        matches!(e, ZmqEvent::Sequence(SequenceEvent::TxAdded { .. }))
+    });
+    let ZmqEvent::Sequence(SequenceEvent::TxAdded { txid: added, .. }) = event else {
+        unreachable!()
+    };
+    assert_eq!(added, txid);
+}
+
+#[test]
+fn test_zmq_discover_without_notifications() {
+    let env = TestEnv::setup().unwrap();
+
+    assert!(env.client.get_zmq_notifications().unwrap().is_empty());
+    assert!(matches!(
+        ZmqSubscriber::discover(&env.client),
+        Err(Error::InvalidConfig(_))
+    ));
+}